@@ -0,0 +1,41 @@
+-- Periodic re-verification of token-gated group members
+
+CREATE TABLE IF NOT EXISTS gate_membership_status (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    conversation_id TEXT NOT NULL,
+    wallet_address VARCHAR(42) NOT NULL,
+    qualifies BOOLEAN NOT NULL DEFAULT true,
+    requirements_met JSONB NOT NULL DEFAULT '[]'::jsonb,  -- RequirementStatus[] from the last check
+    last_checked_at TIMESTAMP WITH TIME ZONE,          -- NULL until the re-verifier has run once
+    failing_since TIMESTAMP WITH TIME ZONE,            -- first failed check since last passing one
+    notified_at TIMESTAMP WITH TIME ZONE,              -- when the eviction webhook was sent
+    last_error TEXT,                                   -- RPC error from the last attempt, if any
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (conversation_id, wallet_address)
+);
+
+CREATE INDEX idx_gate_membership_conversation ON gate_membership_status(conversation_id);
+CREATE INDEX idx_gate_membership_last_checked ON gate_membership_status(last_checked_at NULLS FIRST);
+CREATE INDEX idx_gate_membership_failing ON gate_membership_status(conversation_id, failing_since)
+    WHERE qualifies = false;
+
+-- Per-conversation grace period and optional webhook for eviction reports
+CREATE TABLE IF NOT EXISTS gate_reverify_settings (
+    conversation_id TEXT PRIMARY KEY,
+    grace_period_hours INTEGER NOT NULL DEFAULT 24,
+    webhook_url TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Auto-update updated_at
+CREATE TRIGGER update_gate_membership_status_updated_at BEFORE UPDATE ON gate_membership_status
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TRIGGER update_gate_reverify_settings_updated_at BEFORE UPDATE ON gate_reverify_settings
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON TABLE gate_membership_status IS 'Known members of token-gated conversations and their latest re-verification result';
+COMMENT ON COLUMN gate_membership_status.failing_since IS 'Set on the first failed check; member is reported for removal once the grace period has passed';
+COMMENT ON TABLE gate_reverify_settings IS 'Grace period and eviction webhook per token-gated conversation';
@@ -0,0 +1,8 @@
+-- Holding-duration requirement for token gates
+-- min_hold_days: balance must have stayed >= min_amount for this many days (NULL = no duration check)
+
+ALTER TABLE token_gates
+    ADD COLUMN IF NOT EXISTS min_hold_days INTEGER
+        CHECK (min_hold_days IS NULL OR (min_hold_days >= 1 AND min_hold_days <= 365));
+
+COMMENT ON COLUMN token_gates.min_hold_days IS 'Days the balance must have been held continuously (checked with archive balance reads, one sample per day)';
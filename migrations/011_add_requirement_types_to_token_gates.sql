@@ -0,0 +1,17 @@
+-- Non-token requirement types for token gates
+-- requirement_type: 'token' (balance check), 'basename' (verified Basename ownership),
+--                   'profile_age' (profile older than min_profile_age_days), 'username' (claimed username)
+-- min_profile_age_days: only used by 'profile_age' requirements
+
+ALTER TABLE token_gates
+    ADD COLUMN IF NOT EXISTS requirement_type VARCHAR(20) NOT NULL DEFAULT 'token'
+        CHECK (requirement_type IN ('token', 'basename', 'profile_age', 'username')),
+    ADD COLUMN IF NOT EXISTS min_profile_age_days INTEGER
+        CHECK (min_profile_age_days IS NULL OR (min_profile_age_days >= 1 AND min_profile_age_days <= 3650));
+
+ALTER TABLE token_gates
+    ADD CONSTRAINT token_gates_profile_age_days_required
+        CHECK (requirement_type <> 'profile_age' OR min_profile_age_days IS NOT NULL);
+
+COMMENT ON COLUMN token_gates.requirement_type IS 'token, basename, profile_age or username; combined with the other rows using operator';
+COMMENT ON COLUMN token_gates.min_profile_age_days IS 'Minimum age in days of the wallet''s BlocChat profile (profile_age requirements only)';
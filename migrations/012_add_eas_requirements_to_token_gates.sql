@@ -0,0 +1,19 @@
+-- EAS attestation requirements for token gates
+-- eas_schema_uid: schema the wallet must hold a valid attestation for
+-- eas_attester: optional attester the attestation must come from
+
+ALTER TABLE token_gates DROP CONSTRAINT IF EXISTS token_gates_requirement_type_check;
+ALTER TABLE token_gates
+    ADD CONSTRAINT token_gates_requirement_type_check
+        CHECK (requirement_type IN ('token', 'basename', 'profile_age', 'username', 'eas'));
+
+ALTER TABLE token_gates
+    ADD COLUMN IF NOT EXISTS eas_schema_uid VARCHAR(66),
+    ADD COLUMN IF NOT EXISTS eas_attester VARCHAR(42);
+
+ALTER TABLE token_gates
+    ADD CONSTRAINT token_gates_eas_schema_required
+        CHECK (requirement_type <> 'eas' OR eas_schema_uid ~ '^0x[0-9a-fA-F]{64}$');
+
+COMMENT ON COLUMN token_gates.eas_schema_uid IS 'EAS schema UID (eas requirements only)';
+COMMENT ON COLUMN token_gates.eas_attester IS 'Required attester address, NULL to accept any attester';
@@ -0,0 +1,47 @@
+-- Shop orders: a buyer's purchase of shop items, paid with an on-chain transfer
+
+CREATE TYPE order_status AS ENUM ('pending', 'paid', 'fulfilled', 'refunded');
+
+CREATE TABLE IF NOT EXISTS orders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    shop_id UUID REFERENCES shops(id) ON DELETE SET NULL,  -- orders outlive their shop
+    conversation_id VARCHAR(255) NOT NULL,
+    buyer_address VARCHAR(42) NOT NULL,
+    seller_address VARCHAR(42) NOT NULL,   -- shop owner at checkout time
+    token_address VARCHAR(42),             -- NULL for native ETH
+    token_symbol VARCHAR(20) NOT NULL,
+    total_amount TEXT NOT NULL,            -- smallest token unit, same format as transactions.amount
+    status order_status NOT NULL DEFAULT 'pending',
+    tx_hash VARCHAR(66) UNIQUE,            -- payment transaction, set when the order is paid
+    refund_tx_hash VARCHAR(66),
+    paid_at TIMESTAMP WITH TIME ZONE,
+    fulfilled_at TIMESTAMP WITH TIME ZONE,
+    refunded_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Line items with price and name snapshotted at checkout
+CREATE TABLE IF NOT EXISTS order_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    item_id UUID REFERENCES shop_items(id) ON DELETE SET NULL,
+    name VARCHAR(255) NOT NULL,
+    price TEXT NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_orders_shop_id ON orders(shop_id, created_at DESC);
+CREATE INDEX idx_orders_buyer ON orders(LOWER(buyer_address), created_at DESC);
+CREATE INDEX idx_orders_seller ON orders(LOWER(seller_address), created_at DESC);
+CREATE INDEX idx_orders_pending ON orders(created_at) WHERE status = 'pending';
+CREATE INDEX idx_order_items_order_id ON order_items(order_id);
+
+-- Auto-update updated_at
+CREATE TRIGGER update_orders_updated_at BEFORE UPDATE ON orders
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON TABLE orders IS 'Shop orders; pending until a matching confirmed transaction from buyer to seller is found';
+COMMENT ON COLUMN orders.total_amount IS 'Sum of price * quantity over order_items, in the token''s smallest unit';
+COMMENT ON COLUMN orders.tx_hash IS 'Transaction that paid this order; unique so one transfer cannot pay two orders';
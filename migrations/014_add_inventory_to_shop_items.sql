@@ -0,0 +1,29 @@
+-- Inventory, per-wallet limits and sale windows for shop items
+-- stock: units still available (NULL = unlimited); decremented when an order reserves units
+-- max_per_wallet: most units one buyer may hold across their open and paid orders (NULL = no limit)
+
+ALTER TABLE shop_items
+    ADD COLUMN IF NOT EXISTS stock INTEGER CHECK (stock IS NULL OR stock >= 0),
+    ADD COLUMN IF NOT EXISTS max_per_wallet INTEGER CHECK (max_per_wallet IS NULL OR max_per_wallet > 0),
+    ADD COLUMN IF NOT EXISTS sale_starts_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS sale_ends_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE shop_items
+    ADD CONSTRAINT shop_items_sale_window_check
+        CHECK (sale_starts_at IS NULL OR sale_ends_at IS NULL OR sale_starts_at < sale_ends_at);
+
+-- Pending orders hold their stock until reserved_until, then expire and give it back
+ALTER TYPE order_status ADD VALUE IF NOT EXISTS 'expired';
+
+ALTER TABLE orders
+    ADD COLUMN IF NOT EXISTS reserved_until TIMESTAMP WITH TIME ZONE;
+
+-- Whether this line took units out of stock (items without stock reserve nothing)
+ALTER TABLE order_items
+    ADD COLUMN IF NOT EXISTS reserved BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_order_items_item_id ON order_items(item_id);
+
+COMMENT ON COLUMN shop_items.stock IS 'Units still available; NULL for unlimited. Reserved atomically at checkout';
+COMMENT ON COLUMN shop_items.max_per_wallet IS 'Per-buyer cap across pending, paid and fulfilled orders';
+COMMENT ON COLUMN orders.reserved_until IS 'Pending orders expire after this and release their reserved stock';
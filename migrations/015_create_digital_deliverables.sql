@@ -0,0 +1,25 @@
+-- Digital goods: encrypted deliverables on shop items and pools of single-use license codes
+-- Payloads are AES-256-GCM encrypted with the server key (DELIVERABLE_ENCRYPTION_KEY) and
+-- only decrypted for the buyer wallet of a paid order
+
+ALTER TABLE shop_items
+    ADD COLUMN IF NOT EXISTS deliverable_kind VARCHAR(20)
+        CHECK (deliverable_kind IS NULL OR deliverable_kind IN ('text', 'link', 'file')),
+    ADD COLUMN IF NOT EXISTS deliverable_ciphertext TEXT;
+
+CREATE TABLE IF NOT EXISTS shop_item_license_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    item_id UUID NOT NULL REFERENCES shop_items(id) ON DELETE CASCADE,
+    code_ciphertext TEXT NOT NULL,
+    order_id UUID REFERENCES orders(id) ON DELETE SET NULL,  -- NULL while unassigned
+    assigned_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_license_codes_available ON shop_item_license_codes(item_id, created_at)
+    WHERE order_id IS NULL;
+CREATE INDEX idx_license_codes_order ON shop_item_license_codes(order_id);
+
+COMMENT ON COLUMN shop_items.deliverable_kind IS 'text (secret), link (private URL) or file (storage reference)';
+COMMENT ON COLUMN shop_items.deliverable_ciphertext IS 'base64(nonce || AES-256-GCM ciphertext) of the deliverable';
+COMMENT ON TABLE shop_item_license_codes IS 'Single-use codes handed out one per unit purchased once the order is paid';
@@ -0,0 +1,28 @@
+-- USD-denominated shop pricing
+-- Items may carry a fiat price; checkout converts it to ETH or USDC at the latest
+-- CoinGecko price and locks that quote on the order for a short window.
+
+-- Latest USD price per checkout token, refreshed by the feed poller
+CREATE TABLE IF NOT EXISTS token_prices (
+    coingecko_id VARCHAR(100) PRIMARY KEY,
+    price_usd DOUBLE PRECISION NOT NULL CHECK (price_usd > 0),
+    fetched_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE shop_items
+    ADD COLUMN IF NOT EXISTS price_usd_cents BIGINT CHECK (price_usd_cents IS NULL OR price_usd_cents > 0);
+
+ALTER TABLE orders
+    ADD COLUMN IF NOT EXISTS quote_usd_cents BIGINT,
+    ADD COLUMN IF NOT EXISTS quote_token_price_usd DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS quote_expires_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS min_accepted_amount TEXT;
+
+ALTER TABLE order_items
+    ADD COLUMN IF NOT EXISTS price_usd_cents BIGINT;
+
+COMMENT ON TABLE token_prices IS 'Latest CoinGecko USD price for tokens accepted at checkout';
+COMMENT ON COLUMN shop_items.price_usd_cents IS 'Fiat price in US cents; when set, price is converted at checkout';
+COMMENT ON COLUMN orders.quote_token_price_usd IS 'Token price the USD total was converted at';
+COMMENT ON COLUMN orders.quote_expires_at IS 'Payments recorded after this no longer honour the quote';
+COMMENT ON COLUMN orders.min_accepted_amount IS 'Quoted total minus the slippage tolerance, smallest token unit';
@@ -0,0 +1,36 @@
+-- Holder rules on shop items: a purchase gate and tiered holder discounts
+-- Requirements use the same shape as token_gates (token, basename, profile_age, username, eas)
+-- and are evaluated with the same code at checkout.
+-- rule_type 'gate': buyers must meet it to purchase (at most one per item)
+-- rule_type 'discount': buyers who meet it get discount_bps off; the largest qualifying tier wins
+
+CREATE TABLE IF NOT EXISTS shop_item_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    item_id UUID NOT NULL REFERENCES shop_items(id) ON DELETE CASCADE,
+    rule_type VARCHAR(20) NOT NULL CHECK (rule_type IN ('gate', 'discount')),
+    name VARCHAR(100) NOT NULL,
+    operator VARCHAR(3) NOT NULL DEFAULT 'AND' CHECK (operator IN ('AND', 'OR')),
+    requirements JSONB NOT NULL DEFAULT '[]'::jsonb,
+    discount_bps INTEGER NOT NULL DEFAULT 0 CHECK (discount_bps >= 0 AND discount_bps <= 10000),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT shop_item_rules_discount_required CHECK (rule_type <> 'discount' OR discount_bps > 0)
+);
+
+CREATE INDEX idx_shop_item_rules_item_id ON shop_item_rules(item_id);
+CREATE UNIQUE INDEX idx_shop_item_rules_one_gate ON shop_item_rules(item_id) WHERE rule_type = 'gate';
+
+CREATE TRIGGER update_shop_item_rules_updated_at
+    BEFORE UPDATE ON shop_item_rules
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Which rule admitted the buyer or discounted the line, snapshotted like the price
+ALTER TABLE order_items
+    ADD COLUMN IF NOT EXISTS rule_id UUID REFERENCES shop_item_rules(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS rule_name VARCHAR(100),
+    ADD COLUMN IF NOT EXISTS discount_bps INTEGER NOT NULL DEFAULT 0;
+
+COMMENT ON TABLE shop_item_rules IS 'Holder-only purchase gates and tiered holder discounts for shop items';
+COMMENT ON COLUMN shop_item_rules.requirements IS 'Array of token gate requirements, combined with operator';
+COMMENT ON COLUMN order_items.rule_id IS 'Discount tier (or purchase gate) that applied to this line at checkout';
@@ -0,0 +1,60 @@
+-- Promo codes for shops
+-- percentage: percent_off_bps of the eligible subtotal (2500 = 25%)
+-- fixed: a flat amount off the eligible subtotal, either in US cents (USD-priced orders)
+--        or in the smallest unit of one token (orders paid in that token)
+-- item_ids restricts the code to those items (NULL = whole shop)
+
+CREATE TABLE IF NOT EXISTS shop_promotions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    shop_id UUID NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    code VARCHAR(40) NOT NULL,
+    discount_type VARCHAR(20) NOT NULL CHECK (discount_type IN ('percentage', 'fixed')),
+    percent_off_bps INTEGER CHECK (percent_off_bps IS NULL OR (percent_off_bps > 0 AND percent_off_bps <= 10000)),
+    amount_off TEXT CHECK (amount_off IS NULL OR amount_off ~ '^[0-9]+$'),
+    amount_off_token_address VARCHAR(42),  -- NULL with amount_off for native ETH
+    amount_off_usd_cents BIGINT CHECK (amount_off_usd_cents IS NULL OR amount_off_usd_cents > 0),
+    item_ids UUID[],
+    max_redemptions INTEGER CHECK (max_redemptions IS NULL OR max_redemptions > 0),
+    max_per_wallet INTEGER CHECK (max_per_wallet IS NULL OR max_per_wallet > 0),
+    starts_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT shop_promotions_percentage_required
+        CHECK (discount_type <> 'percentage' OR percent_off_bps IS NOT NULL),
+    CONSTRAINT shop_promotions_fixed_amount_required
+        CHECK (discount_type <> 'fixed' OR ((amount_off IS NULL) <> (amount_off_usd_cents IS NULL))),
+    CONSTRAINT shop_promotions_window_check
+        CHECK (starts_at IS NULL OR expires_at IS NULL OR starts_at < expires_at)
+);
+
+CREATE UNIQUE INDEX idx_shop_promotions_code ON shop_promotions(shop_id, UPPER(code));
+
+CREATE TRIGGER update_shop_promotions_updated_at
+    BEFORE UPDATE ON shop_promotions
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- One redemption per order; redemptions of expired or refunded orders stop counting
+CREATE TABLE IF NOT EXISTS shop_promotion_redemptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    promotion_id UUID NOT NULL REFERENCES shop_promotions(id) ON DELETE CASCADE,
+    order_id UUID NOT NULL UNIQUE REFERENCES orders(id) ON DELETE CASCADE,
+    buyer_address VARCHAR(42) NOT NULL,
+    discount_amount TEXT NOT NULL,
+    discount_usd_cents BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_promotion_redemptions_promotion ON shop_promotion_redemptions(promotion_id, buyer_address);
+
+ALTER TABLE orders
+    ADD COLUMN IF NOT EXISTS promotion_id UUID REFERENCES shop_promotions(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS promo_code VARCHAR(40),
+    ADD COLUMN IF NOT EXISTS discount_amount TEXT,
+    ADD COLUMN IF NOT EXISTS discount_usd_cents BIGINT;
+
+COMMENT ON TABLE shop_promotions IS 'Shop promo codes with usage limits, per-wallet limits, expiry and item restrictions';
+COMMENT ON TABLE shop_promotion_redemptions IS 'Promo code use per order; only pending, paid and fulfilled orders count toward limits';
+COMMENT ON COLUMN orders.discount_amount IS 'Promo discount already taken off total_amount, smallest token unit';
@@ -0,0 +1,18 @@
+-- Item view events for shop analytics (conversion from views to orders)
+-- viewer_address is optional: anonymous views still count toward totals
+
+CREATE TABLE IF NOT EXISTS shop_item_views (
+    id BIGSERIAL PRIMARY KEY,
+    shop_id UUID NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    item_id UUID NOT NULL REFERENCES shop_items(id) ON DELETE CASCADE,
+    viewer_address VARCHAR(42),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_shop_item_views_shop_created ON shop_item_views(shop_id, created_at);
+CREATE INDEX idx_shop_item_views_item_created ON shop_item_views(item_id, created_at);
+
+-- Analytics aggregate paid orders by payment time
+CREATE INDEX IF NOT EXISTS idx_orders_shop_paid_at ON orders(shop_id, paid_at) WHERE paid_at IS NOT NULL;
+
+COMMENT ON TABLE shop_item_views IS 'One row per item view; aggregated for shop analytics';
@@ -0,0 +1,34 @@
+-- Verified-purchase reviews for shop items
+-- Only the buyer wallet of a paid order can review, once per item in that order.
+-- Hidden reviews (admin moderation) are left out of listings and rating aggregates.
+
+CREATE TABLE IF NOT EXISTS shop_item_reviews (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    item_id UUID NOT NULL REFERENCES shop_items(id) ON DELETE CASCADE,
+    shop_id UUID NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    order_id UUID NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    buyer_address VARCHAR(42) NOT NULL,
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    body TEXT CHECK (body IS NULL OR char_length(body) <= 2000),
+    seller_reply TEXT CHECK (seller_reply IS NULL OR char_length(seller_reply) <= 1000),
+    seller_replied_at TIMESTAMP WITH TIME ZONE,
+    is_hidden BOOLEAN NOT NULL DEFAULT false,
+    hidden_reason TEXT,
+    hidden_by VARCHAR(42),
+    hidden_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (order_id, item_id)
+);
+
+CREATE INDEX idx_item_reviews_item ON shop_item_reviews(item_id, created_at DESC) WHERE NOT is_hidden;
+CREATE INDEX idx_item_reviews_shop ON shop_item_reviews(shop_id) WHERE NOT is_hidden;
+CREATE INDEX idx_item_reviews_hidden ON shop_item_reviews(hidden_at DESC) WHERE is_hidden;
+
+CREATE TRIGGER update_shop_item_reviews_updated_at
+    BEFORE UPDATE ON shop_item_reviews
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON TABLE shop_item_reviews IS 'Star ratings and text from buyers with a paid order for the item';
+COMMENT ON COLUMN shop_item_reviews.is_hidden IS 'Set by admin moderation; hidden reviews do not count toward ratings';
@@ -0,0 +1,20 @@
+-- Marketplace discovery across shops in public groups
+-- Items get an optional category and free-form tags, plus a weighted full-text
+-- vector over name (A) and description (B) for search.
+
+ALTER TABLE shop_items
+    ADD COLUMN IF NOT EXISTS category VARCHAR(50)
+        CHECK (category IS NULL OR category ~ '^[a-z0-9][a-z0-9-]*$'),
+    ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}'
+        CHECK (cardinality(tags) <= 10),
+    ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('english', coalesce(description, '')), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_shop_items_search ON shop_items USING gin(search_vector);
+CREATE INDEX IF NOT EXISTS idx_shop_items_category ON shop_items(category) WHERE category IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_shop_items_tags ON shop_items USING gin(tags);
+
+COMMENT ON COLUMN shop_items.category IS 'Lowercase slug, e.g. "merch" or "digital-art"';
+COMMENT ON COLUMN shop_items.tags IS 'Up to 10 lowercase tags for marketplace filtering';
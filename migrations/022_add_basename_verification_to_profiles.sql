@@ -0,0 +1,16 @@
+-- On-chain Basename verification
+-- A basename is only stored once it forward-resolves to the profile's wallet through
+-- the Base L2 resolver. The refresh job re-checks stored names and clears those whose
+-- record no longer points at the wallet. Existing names start unchecked so the first
+-- pass verifies or clears them.
+
+ALTER TABLE user_profiles
+    ADD COLUMN IF NOT EXISTS basename_verified_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS basename_checked_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_user_profiles_basename_checked
+    ON user_profiles (basename_checked_at NULLS FIRST)
+    WHERE basename IS NOT NULL;
+
+COMMENT ON COLUMN user_profiles.basename_verified_at IS 'Last time the basename resolved to this wallet on Base';
+COMMENT ON COLUMN user_profiles.basename_checked_at IS 'Last on-chain check, successful or not (refresh job / reverse discovery)';
@@ -0,0 +1,23 @@
+-- ENS identity for profiles
+-- Primary mainnet ENS name (reverse record confirmed by a forward lookup) and a few
+-- text records, cached on the profile and re-resolved once ens_resolved_at is older
+-- than ENS_CACHE_TTL_SECS.
+
+ALTER TABLE user_profiles
+    ADD COLUMN IF NOT EXISTS ens_name TEXT,
+    ADD COLUMN IF NOT EXISTS ens_avatar TEXT,
+    ADD COLUMN IF NOT EXISTS ens_url TEXT,
+    ADD COLUMN IF NOT EXISTS ens_twitter TEXT,
+    ADD COLUMN IF NOT EXISTS ens_github TEXT,
+    ADD COLUMN IF NOT EXISTS ens_discoverable BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS ens_resolved_at TIMESTAMP WITH TIME ZONE;
+
+-- Find discoverable ENS names during search
+CREATE INDEX IF NOT EXISTS idx_user_profiles_ens_discoverable
+    ON user_profiles (LOWER(ens_name))
+    WHERE ens_name IS NOT NULL AND ens_discoverable = true;
+
+COMMENT ON COLUMN user_profiles.ens_name IS 'Verified primary ENS name on Ethereum mainnet (e.g. vitalik.eth)';
+COMMENT ON COLUMN user_profiles.ens_avatar IS 'ENS avatar record, resolved to an HTTP(S) URL where possible';
+COMMENT ON COLUMN user_profiles.ens_discoverable IS 'When true, users can find this profile by searching their ENS name';
+COMMENT ON COLUMN user_profiles.ens_resolved_at IS 'Last ENS lookup; NULL means never resolved';
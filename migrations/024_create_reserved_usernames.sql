@@ -0,0 +1,58 @@
+-- Reserved / blocked usernames and lookalike protection
+-- Usernames are compared by a confusable "skeleton": lowercase, underscores dropped,
+-- digits folded to the letters they imitate (0→o, 1→l, 3→e, 4→a, 5→s, 7→t, 8→b...),
+-- i→l, and rn→m / vv→w. "coinbase", "c0inbase" and "co_inbase" share one skeleton.
+
+CREATE OR REPLACE FUNCTION username_skeleton(name TEXT)
+RETURNS TEXT AS $$
+    SELECT replace(replace(translate(lower(name), '0123456789i_', 'olzeasbtbgl'), 'rn', 'm'), 'vv', 'w')
+$$ LANGUAGE SQL IMMUTABLE STRICT;
+
+ALTER TABLE user_profiles
+    ADD COLUMN IF NOT EXISTS username_skeleton TEXT
+        GENERATED ALWAYS AS (username_skeleton(username)) STORED;
+
+-- Not unique: lookalikes claimed before this migration are grandfathered
+CREATE INDEX IF NOT EXISTS idx_user_profiles_username_skeleton
+    ON user_profiles (username_skeleton)
+    WHERE username_skeleton IS NOT NULL;
+
+-- 'reserved' names are held for a brand or team and can be assigned to a wallet;
+-- 'blocked' names can never be claimed
+CREATE TABLE IF NOT EXISTS reserved_usernames (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(30) NOT NULL,
+    skeleton TEXT NOT NULL GENERATED ALWAYS AS (username_skeleton(name)) STORED,
+    kind VARCHAR(10) NOT NULL DEFAULT 'reserved' CHECK (kind IN ('reserved', 'blocked')),
+    reason TEXT,
+    assigned_wallet VARCHAR(42),
+    created_by VARCHAR(42),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (kind = 'reserved' OR assigned_wallet IS NULL)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reserved_usernames_skeleton ON reserved_usernames(skeleton);
+
+CREATE TRIGGER update_reserved_usernames_updated_at BEFORE UPDATE ON reserved_usernames
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+INSERT INTO reserved_usernames (name, kind, reason) VALUES
+    ('admin', 'blocked', 'Staff impersonation'),
+    ('administrator', 'blocked', 'Staff impersonation'),
+    ('moderator', 'blocked', 'Staff impersonation'),
+    ('support', 'blocked', 'Staff impersonation'),
+    ('help', 'blocked', 'Staff impersonation'),
+    ('security', 'blocked', 'Staff impersonation'),
+    ('official', 'blocked', 'Staff impersonation'),
+    ('system', 'blocked', 'Staff impersonation'),
+    ('root', 'blocked', 'Staff impersonation'),
+    ('staff', 'blocked', 'Staff impersonation'),
+    ('blocchat', 'reserved', 'Platform name'),
+    ('coinbase', 'reserved', 'Brand'),
+    ('base', 'reserved', 'Brand')
+ON CONFLICT DO NOTHING;
+
+COMMENT ON FUNCTION username_skeleton(TEXT) IS 'Confusable-folded form of a username; equal skeletons collide';
+COMMENT ON TABLE reserved_usernames IS 'Admin-managed usernames that can''t be claimed freely';
+COMMENT ON COLUMN reserved_usernames.assigned_wallet IS 'Wallet allowed to claim a reserved name (verified brand)';
@@ -0,0 +1,19 @@
+-- Username history
+-- Every rename is recorded. For the grace period (locked_until) the old name keeps
+-- resolving to the renamed profile and nobody else can claim it or a lookalike.
+
+CREATE TABLE IF NOT EXISTS username_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    wallet_address VARCHAR(42) NOT NULL,
+    old_username VARCHAR(30) NOT NULL,
+    new_username VARCHAR(30) NOT NULL,
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    locked_until TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_username_history_old ON username_history (LOWER(old_username), changed_at DESC);
+CREATE INDEX IF NOT EXISTS idx_username_history_old_skeleton ON username_history (username_skeleton(old_username));
+CREATE INDEX IF NOT EXISTS idx_username_history_wallet ON username_history (wallet_address, changed_at DESC);
+
+COMMENT ON TABLE username_history IS 'Past usernames per wallet, for redirects and locking released names';
+COMMENT ON COLUMN username_history.locked_until IS 'End of the grace period: redirect served and name unclaimable until then';
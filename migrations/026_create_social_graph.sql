@@ -0,0 +1,27 @@
+-- Social graph: follows and per-user block lists
+-- Two profiles following each other are "contacts". A block in either direction
+-- removes follows between the pair and hides each from the other's search.
+
+CREATE TABLE IF NOT EXISTS profile_follows (
+    follower_wallet VARCHAR(42) NOT NULL REFERENCES user_profiles(wallet_address) ON DELETE CASCADE,
+    followee_wallet VARCHAR(42) NOT NULL REFERENCES user_profiles(wallet_address) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (follower_wallet, followee_wallet),
+    CHECK (follower_wallet != followee_wallet)
+);
+
+CREATE INDEX IF NOT EXISTS idx_profile_follows_followee ON profile_follows(followee_wallet, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_profile_follows_follower ON profile_follows(follower_wallet, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS profile_blocks (
+    blocker_wallet VARCHAR(42) NOT NULL REFERENCES user_profiles(wallet_address) ON DELETE CASCADE,
+    blocked_wallet VARCHAR(42) NOT NULL REFERENCES user_profiles(wallet_address) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (blocker_wallet, blocked_wallet),
+    CHECK (blocker_wallet != blocked_wallet)
+);
+
+CREATE INDEX IF NOT EXISTS idx_profile_blocks_blocked ON profile_blocks(blocked_wallet);
+
+COMMENT ON TABLE profile_follows IS 'Who follows whom; mutual follows are contacts';
+COMMENT ON TABLE profile_blocks IS 'Per-user block lists, enforced in search, follows and typing indicators';
@@ -0,0 +1,16 @@
+-- Per-profile privacy settings
+-- Discoverability controls which search fields and direct lookups find a profile
+-- (contacts can always look it up); visibility controls who can read it at all;
+-- bio and avatar can be hidden from everyone but contacts.
+
+ALTER TABLE user_profiles
+    ADD COLUMN IF NOT EXISTS discoverable_by_username BOOLEAN NOT NULL DEFAULT true,
+    ADD COLUMN IF NOT EXISTS discoverable_by_wallet BOOLEAN NOT NULL DEFAULT true,
+    ADD COLUMN IF NOT EXISTS discoverable_by_display_name BOOLEAN NOT NULL DEFAULT true,
+    ADD COLUMN IF NOT EXISTS profile_visibility VARCHAR(10) NOT NULL DEFAULT 'everyone'
+        CHECK (profile_visibility IN ('everyone', 'contacts', 'nobody')),
+    ADD COLUMN IF NOT EXISTS hide_bio_from_non_contacts BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS hide_avatar_from_non_contacts BOOLEAN NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN user_profiles.profile_visibility IS 'Who can read the profile: everyone, contacts (mutual follows) or nobody';
+COMMENT ON COLUMN user_profiles.hide_avatar_from_non_contacts IS 'Also hides the ENS avatar';
@@ -0,0 +1,47 @@
+-- Fuzzy profile and group search backed by pg_trgm
+-- Replaces the ILIKE scans with trigram indexes, and ranks each row by its best
+-- matching attribute: exact > prefix > substring > fuzzy (trigram similarity).
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- The full text index on group names was never used by search
+DROP INDEX IF EXISTS idx_public_groups_name;
+
+CREATE INDEX IF NOT EXISTS idx_public_groups_name_trgm ON public_groups USING gin(name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_public_groups_description_trgm ON public_groups USING gin(description gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_user_profiles_username_trgm ON user_profiles USING gin(username gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_user_profiles_display_name_trgm ON user_profiles USING gin(display_name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_user_profiles_wallet_trgm ON user_profiles USING gin(wallet_address gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_user_profiles_basename_trgm ON user_profiles USING gin(basename gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_user_profiles_ens_name_trgm ON user_profiles USING gin(ens_name gin_trgm_ops);
+
+-- '%q%' with LIKE wildcards in q escaped
+CREATE OR REPLACE FUNCTION search_like_pattern(q TEXT) RETURNS TEXT AS $$
+    SELECT '%' || replace(replace(replace(q, '\', '\\'), '%', '\%'), '_', '\_') || '%'
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- How value matches q: exact, prefix, contains, fuzzy, or NULL for no match.
+-- STABLE rather than IMMUTABLE since % and <% read the pg_trgm threshold settings.
+CREATE OR REPLACE FUNCTION search_match_kind(value TEXT, q TEXT) RETURNS TEXT AS $$
+    SELECT CASE
+        WHEN value IS NULL OR q = '' THEN NULL
+        WHEN lower(value) = lower(q) THEN 'exact'
+        WHEN starts_with(lower(value), lower(q)) THEN 'prefix'
+        WHEN strpos(lower(value), lower(q)) > 0 THEN 'contains'
+        WHEN value % q OR q <% value THEN 'fuzzy'
+    END
+$$ LANGUAGE SQL STABLE;
+
+-- Relevance of value for q: exact 3, prefix 2-3, contains 1-2, fuzzy 0-1, NULL for no match
+CREATE OR REPLACE FUNCTION search_match_score(value TEXT, q TEXT) RETURNS REAL AS $$
+    SELECT CASE search_match_kind(value, q)
+        WHEN 'exact' THEN 3
+        WHEN 'prefix' THEN 2 + similarity(value, q)
+        WHEN 'contains' THEN 1 + word_similarity(q, value)
+        WHEN 'fuzzy' THEN GREATEST(similarity(value, q), word_similarity(q, value))
+    END
+$$ LANGUAGE SQL STABLE;
+
+COMMENT ON FUNCTION search_match_kind(TEXT, TEXT) IS 'Search highlight: how a field matched the query';
+COMMENT ON FUNCTION search_match_score(TEXT, TEXT) IS 'Search ranking score of a field for the query, before popularity';
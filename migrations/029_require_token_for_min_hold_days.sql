@@ -0,0 +1,8 @@
+-- Holding periods are replayed from ERC-20 Transfer logs, which native ETH doesn't emit.
+-- NOT VALID keeps existing rows; those gates now fail the holding check until edited.
+
+ALTER TABLE token_gates
+    ADD CONSTRAINT token_gates_hold_days_need_token
+        CHECK (min_hold_days IS NULL OR token_address IS NOT NULL) NOT VALID;
+
+COMMENT ON COLUMN token_gates.min_hold_days IS 'Days the ERC-20 balance must have stayed >= min_amount (replayed from Transfer logs, any dip resets it)';
@@ -0,0 +1,42 @@
+-- Transaction hashes are stored lowercase with a 0x prefix (payment_service::canonical_tx_hash).
+-- Before this, one transfer could be recorded under several spellings and pay several orders.
+-- Per hash, the row already in canonical form (else the earliest) keeps it; other spellings
+-- of a transaction are marked failed so they can't match again, while orders that already
+-- used a second spelling keep it for review.
+
+CREATE TEMP TABLE canonical_tx_hashes AS
+SELECT id, tx_hash, '0x' || LOWER(RIGHT(tx_hash, 64)) AS canonical
+FROM transactions
+WHERE tx_hash ~ '^(0[xX])?[0-9a-fA-F]{64}$';
+
+CREATE TEMP TABLE canonical_tx_hash_keepers AS
+SELECT DISTINCT ON (c.canonical) c.id, c.canonical
+FROM canonical_tx_hashes c
+JOIN transactions t ON t.id = c.id
+ORDER BY c.canonical, (c.tx_hash = c.canonical) DESC, t.created_at, t.id;
+
+UPDATE transactions t SET status = 'failed'
+FROM canonical_tx_hashes c
+WHERE t.id = c.id AND c.id NOT IN (SELECT id FROM canonical_tx_hash_keepers);
+
+UPDATE transactions t SET tx_hash = k.canonical
+FROM canonical_tx_hash_keepers k
+WHERE t.id = k.id AND t.tx_hash <> k.canonical;
+
+UPDATE orders o SET tx_hash = k.canonical
+FROM (
+    SELECT DISTINCT ON (canonical) id, '0x' || LOWER(RIGHT(tx_hash, 64)) AS canonical
+    FROM orders
+    WHERE tx_hash ~ '^(0[xX])?[0-9a-fA-F]{64}$'
+    ORDER BY '0x' || LOWER(RIGHT(tx_hash, 64)), (tx_hash = '0x' || LOWER(RIGHT(tx_hash, 64))) DESC, paid_at, id
+) k
+WHERE o.id = k.id AND o.tx_hash <> k.canonical;
+
+DROP TABLE canonical_tx_hash_keepers;
+DROP TABLE canonical_tx_hashes;
+
+-- NOT VALID keeps rows that were never a valid hash
+ALTER TABLE transactions
+    ADD CONSTRAINT transactions_tx_hash_canonical CHECK (tx_hash ~ '^0x[0-9a-f]{64}$') NOT VALID;
+
+COMMENT ON COLUMN transactions.tx_hash IS 'Lowercase 0x-prefixed hash; one row per on-chain transaction';
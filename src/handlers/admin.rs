@@ -1,10 +1,13 @@
-use actix_web::{cookie::Cookie, get, post, web, HttpResponse, Responder, Scope};
+use actix_web::{cookie::Cookie, delete, get, post, web, HttpResponse, Responder, Scope};
 use sqlx::PgPool;
 use std::env;
 
 use crate::{
-    models::{AuthRequest, AuthResponse, NonceRequest, NonceResponse, NonceStore, SessionStore},
-    services::admin_service,
+    models::{
+        AssignUsernameRequest, AuthRequest, AuthResponse, HideReviewRequest, NonceRequest, NonceResponse, NonceStore,
+        ProfileResponse, ReserveUsernameRequest, SessionStore,
+    },
+    services::{admin_service, reserved_username_service, review_service},
 };
 
 pub fn configure() -> Scope {
@@ -21,6 +24,15 @@ pub fn configure() -> Scope {
         .service(get_users)
         .service(get_groups)
         .service(get_disputes)
+        // Moderation (requires an admin session)
+        .service(get_reviews)
+        .service(hide_review)
+        .service(unhide_review)
+        // Reserved usernames (requires an admin session)
+        .service(get_reserved_usernames)
+        .service(reserve_username)
+        .service(release_username)
+        .service(assign_username)
 }
 
 /// Get a nonce for wallet signing
@@ -318,3 +330,212 @@ async fn get_disputes() -> impl Responder {
     // contract events for disputed escrows.
     HttpResponse::Ok().json(serde_json::json!([]))
 }
+
+// ===== Moderation Endpoints =====
+
+/// Wallet of the admin behind the request's session, or the error response to return
+pub(crate) fn require_admin(session_store: &SessionStore, req: &actix_web::HttpRequest) -> Result<String, HttpResponse> {
+    let unauthorized = || {
+        HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Admin session required"
+        }))
+    };
+
+    let token = extract_token(req).ok_or_else(unauthorized)?;
+    let wallet_address = admin_service::verify_session(session_store, &token).map_err(|_| unauthorized())?;
+    if !admin_service::is_admin(&wallet_address, &get_admin_addresses()) {
+        return Err(unauthorized());
+    }
+    Ok(wallet_address)
+}
+
+/// List shop item reviews, optionally only hidden (?hidden=true) or visible ones
+#[get("/reviews")]
+async fn get_reviews(
+    pool: web::Data<PgPool>,
+    session_store: web::Data<SessionStore>,
+    req: actix_web::HttpRequest,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&session_store, &req) {
+        return response;
+    }
+    let hidden = query.get("hidden").and_then(|s| s.parse::<bool>().ok());
+    let limit = query
+        .get("limit")
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(50);
+
+    match review_service::get_reviews_for_moderation(&pool, hidden, limit).await {
+        Ok(reviews) => HttpResponse::Ok().json(reviews),
+        Err(e) => {
+            log::error!("Failed to get reviews: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to fetch reviews"
+            }))
+        }
+    }
+}
+
+/// Hide an abusive review from listings and ratings
+#[post("/reviews/{review_id}/hide")]
+async fn hide_review(
+    pool: web::Data<PgPool>,
+    session_store: web::Data<SessionStore>,
+    req: actix_web::HttpRequest,
+    review_id: web::Path<uuid::Uuid>,
+    body: web::Json<HideReviewRequest>,
+) -> impl Responder {
+    let admin_address = match require_admin(&session_store, &req) {
+        Ok(wallet_address) => wallet_address,
+        Err(response) => return response,
+    };
+
+    let reason = body.into_inner().reason;
+    match review_service::set_review_hidden(&pool, &review_id, true, reason, &admin_address).await {
+        Ok(review) => {
+            log::info!("Review {} hidden by {}", review_id, admin_address);
+            HttpResponse::Ok().json(review)
+        }
+        Err(e) => moderation_error(e),
+    }
+}
+
+#[post("/reviews/{review_id}/unhide")]
+async fn unhide_review(
+    pool: web::Data<PgPool>,
+    session_store: web::Data<SessionStore>,
+    req: actix_web::HttpRequest,
+    review_id: web::Path<uuid::Uuid>,
+) -> impl Responder {
+    let admin_address = match require_admin(&session_store, &req) {
+        Ok(wallet_address) => wallet_address,
+        Err(response) => return response,
+    };
+
+    match review_service::set_review_hidden(&pool, &review_id, false, None, &admin_address).await {
+        Ok(review) => {
+            log::info!("Review {} restored by {}", review_id, admin_address);
+            HttpResponse::Ok().json(review)
+        }
+        Err(e) => moderation_error(e),
+    }
+}
+
+fn moderation_error(e: anyhow::Error) -> HttpResponse {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::RowNotFound) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Review not found"
+        })),
+        _ => {
+            log::error!("Failed to moderate review: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to moderate review"
+            }))
+        }
+    }
+}
+
+// ── Reserved usernames ──
+
+#[get("/usernames/reserved")]
+async fn get_reserved_usernames(
+    pool: web::Data<PgPool>,
+    session_store: web::Data<SessionStore>,
+    req: actix_web::HttpRequest,
+) -> impl Responder {
+    if let Err(response) = require_admin(&session_store, &req) {
+        return response;
+    }
+
+    match reserved_username_service::list_reserved(&pool).await {
+        Ok(reserved) => HttpResponse::Ok().json(reserved),
+        Err(e) => reserved_username_error(e),
+    }
+}
+
+/// Reserve a name for a brand (optionally for a given wallet) or block it outright
+#[post("/usernames/reserved")]
+async fn reserve_username(
+    pool: web::Data<PgPool>,
+    session_store: web::Data<SessionStore>,
+    req: actix_web::HttpRequest,
+    body: web::Json<ReserveUsernameRequest>,
+) -> impl Responder {
+    let admin_address = match require_admin(&session_store, &req) {
+        Ok(wallet_address) => wallet_address,
+        Err(response) => return response,
+    };
+
+    match reserved_username_service::reserve(&pool, body.into_inner(), &admin_address).await {
+        Ok(reserved) => {
+            log::info!("Username '{}' {} by {}", reserved.name, reserved.kind, admin_address);
+            HttpResponse::Created().json(reserved)
+        }
+        Err(e) => reserved_username_error(e),
+    }
+}
+
+#[delete("/usernames/reserved/{name}")]
+async fn release_username(
+    pool: web::Data<PgPool>,
+    session_store: web::Data<SessionStore>,
+    req: actix_web::HttpRequest,
+    name: web::Path<String>,
+) -> impl Responder {
+    let admin_address = match require_admin(&session_store, &req) {
+        Ok(wallet_address) => wallet_address,
+        Err(response) => return response,
+    };
+
+    match reserved_username_service::release(&pool, &name).await {
+        Ok(_) => {
+            log::info!("Username '{}' released by {}", name, admin_address);
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => reserved_username_error(e),
+    }
+}
+
+/// Give a reserved name to a verified brand's wallet and set it on their profile
+#[post("/usernames/reserved/{name}/assign")]
+async fn assign_username(
+    pool: web::Data<PgPool>,
+    session_store: web::Data<SessionStore>,
+    req: actix_web::HttpRequest,
+    name: web::Path<String>,
+    body: web::Json<AssignUsernameRequest>,
+) -> impl Responder {
+    let admin_address = match require_admin(&session_store, &req) {
+        Ok(wallet_address) => wallet_address,
+        Err(response) => return response,
+    };
+
+    match reserved_username_service::assign(&pool, &name, &body.wallet_address, &admin_address).await {
+        Ok((reserved, profile)) => HttpResponse::Ok().json(serde_json::json!({
+            "reserved": reserved,
+            "profile": ProfileResponse::from(profile),
+        })),
+        Err(e) => reserved_username_error(e),
+    }
+}
+
+fn reserved_username_error(e: anyhow::Error) -> HttpResponse {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::RowNotFound) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Reserved username not found"
+        })),
+        Some(sqlx::Error::Database(db)) if db.is_unique_violation() => HttpResponse::Conflict().json(serde_json::json!({
+            "error": "That name or a lookalike is already on the reserved list"
+        })),
+        Some(_) => {
+            log::error!("Failed to manage reserved usernames: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to manage reserved usernames"
+            }))
+        }
+        None => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": e.to_string()
+        })),
+    }
+}
@@ -2,13 +2,16 @@ use actix_web::{delete, get, post, put, web, HttpResponse, Responder, Scope};
 use sqlx::PgPool;
 
 use crate::models::group::{CreatePublicGroupRequest, UpdatePublicGroupRequest, PublicGroupResponse};
-use crate::services::group_service;
+use crate::services::{group_service, image_service};
 
 #[post("")]
 async fn register_group(
     pool: web::Data<PgPool>,
     req: web::Json<CreatePublicGroupRequest>,
 ) -> impl Responder {
+    if let Err(response) = check_image_url(req.image_url.as_deref()) {
+        return response;
+    }
     match group_service::register_group(&pool, req.into_inner()).await {
         Ok(group) => HttpResponse::Created().json(PublicGroupResponse::from(group)),
         Err(e) => {
@@ -37,10 +40,21 @@ async fn search_groups(
         .and_then(|s| s.parse::<i64>().ok())
         .unwrap_or(20)
         .min(50);
+    let offset = query
+        .get("offset")
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(0)
+        .max(0);
 
-    match group_service::search_groups(&pool, q, limit).await {
+    match group_service::search_groups(&pool, q, limit, offset).await {
         Ok(groups) => {
-            let results: Vec<PublicGroupResponse> = groups.into_iter().map(|g| g.into()).collect();
+            let results: Vec<PublicGroupResponse> = groups
+                .into_iter()
+                .map(|(g, highlight)| PublicGroupResponse {
+                    highlight: Some(highlight),
+                    ..g.into()
+                })
+                .collect();
             HttpResponse::Ok().json(results)
         }
         Err(e) => {
@@ -77,6 +91,9 @@ async fn update_group(
     conversation_id: web::Path<String>,
     req: web::Json<UpdatePublicGroupRequest>,
 ) -> impl Responder {
+    if let Err(response) = check_image_url(req.image_url.as_deref()) {
+        return response;
+    }
     match group_service::update_group(&pool, &conversation_id, req.into_inner()).await {
         Ok(group) => HttpResponse::Ok().json(PublicGroupResponse::from(group)),
         Err(sqlx::Error::RowNotFound) => HttpResponse::NotFound().json(serde_json::json!({
@@ -107,6 +124,18 @@ async fn delete_group(
     }
 }
 
+/// Group images must be uploaded through /api/uploads/images; empty clears the image
+fn check_image_url(image_url: Option<&str>) -> Result<(), HttpResponse> {
+    match image_url {
+        Some(url) if !url.is_empty() && !image_service::is_uploaded_image_url(url) => {
+            Err(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "image_url must be an image uploaded through /api/uploads/images"
+            })))
+        }
+        _ => Ok(()),
+    }
+}
+
 pub fn configure() -> Scope {
     web::scope("/groups")
         .service(search_groups)  // Must come before /{conversation_id}
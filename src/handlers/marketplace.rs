@@ -0,0 +1,44 @@
+use actix_web::{get, web, HttpResponse, Responder, Scope};
+use sqlx::PgPool;
+
+use crate::models::MarketplaceSearchQuery;
+use crate::services::marketplace_service;
+
+/// Search items across all shops in public groups
+#[get("/search")]
+async fn search_items(
+    pool: web::Data<PgPool>,
+    query: web::Query<MarketplaceSearchQuery>,
+) -> impl Responder {
+    match marketplace_service::search_items(&pool, query.into_inner()).await {
+        Ok(results) => HttpResponse::Ok().json(results),
+        Err(e) if e.downcast_ref::<sqlx::Error>().is_some() => {
+            log::error!("Marketplace search failed: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Search failed"
+            }))
+        }
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": e.to_string()
+        })),
+    }
+}
+
+#[get("/categories")]
+async fn get_categories(pool: web::Data<PgPool>) -> impl Responder {
+    match marketplace_service::get_categories(&pool).await {
+        Ok(categories) => HttpResponse::Ok().json(categories),
+        Err(e) => {
+            log::error!("Failed to get marketplace categories: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to get categories"
+            }))
+        }
+    }
+}
+
+pub fn configure() -> Scope {
+    web::scope("/marketplace")
+        .service(search_items)
+        .service(get_categories)
+}
@@ -9,3 +9,5 @@ pub mod groups;
 pub mod alpha_bot;
 pub mod ai;
 pub mod feeds;
+pub mod marketplace;
+pub mod uploads;
@@ -19,6 +19,9 @@ async fn create_transaction(
 ) -> impl Responder {
     match payment_service::create_transaction(&pool, req.into_inner()).await {
         Ok(tx) => HttpResponse::Created().json(TransactionResponse::from(tx)),
+        Err(e) if e.downcast_ref::<sqlx::Error>().is_none() => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": e.to_string()
+        })),
         Err(e) => {
             log::error!("Failed to create transaction: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
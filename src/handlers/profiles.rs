@@ -1,25 +1,53 @@
-use actix_web::{get, post, put, web, HttpResponse, Responder, Scope};
+use actix_web::http::header::{self, EntityTag, IfNoneMatch};
+use actix_web::{delete, get, post, put, web, HttpMessage, HttpRequest, HttpResponse, Responder, Scope};
+use sha3::{Digest, Sha3_256};
 use sqlx::PgPool;
 
-use crate::models::{ClaimUsernameRequest, UpdateProfileRequest, ProfileResponse};
-use crate::services::profile_service;
+use crate::models::{
+    AuthRequest, AuthResponse, BatchProfilesRequest, BatchProfilesResponse, ClaimUsernameRequest, NonceRequest, NonceResponse, NonceStore, ProfileResponse,
+    ProfileSessionStore, SocialListQuery, UpdatePrivacyRequest, UpdateProfileRequest,
+};
+use crate::services::profile_service::Lookup;
+use crate::services::{account_service, admin_service, profile_service, social_service};
+
+const MAX_BATCH_SIZE: usize = 500;
 
 pub fn configure() -> Scope {
     web::scope("/profiles")
         .service(get_or_create)
+        .service(get_sign_in_nonce)
+        .service(sign_in)
+        .service(get_privacy)
+        .service(update_privacy)
+        .service(export_account)
+        .service(delete_account)
+        .service(get_batch)
         .service(search)  // Must come before /{wallet_address}
         .service(check_username)
         .service(get_by_username)
         .service(get_by_inbox_id)
         .service(claim_username)
         .service(update_profile)
+        .service(follow)
+        .service(unfollow)
+        .service(block)
+        .service(unblock)
+        .service(get_followers)
+        .service(get_following)
+        .service(get_contacts)
+        .service(get_blocked)
+        .service(get_suggestions)
+        .service(get_relationship)
         .service(get_by_wallet)  // Must come last since it catches any path
 }
 
-/// Get or create profile (called on app load)
+/// Get or create profile (called on app load). An existing profile is returned as
+/// the caller may see it, in full only with the wallet's own session
 #[post("/init")]
 async fn get_or_create(
     pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
     req: web::Json<serde_json::Value>,
 ) -> impl Responder {
     let wallet_address = match req.get("wallet_address").and_then(|v| v.as_str()) {
@@ -36,14 +64,20 @@ async fn get_or_create(
         })),
     };
     
-    match profile_service::get_or_create_profile(&pool, wallet_address, inbox_id).await {
-        Ok(profile) => HttpResponse::Ok().json(ProfileResponse::from(profile)),
+    let profile = match profile_service::get_or_create_profile(&pool, wallet_address, inbox_id).await {
+        Ok(profile) => profile,
         Err(e) => {
             log::error!("Failed to get/create profile: {}", e);
-            HttpResponse::InternalServerError().json(serde_json::json!({
+            return HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": "Failed to initialize profile"
-            }))
+            }));
         }
+    };
+    let viewer = viewer_wallet(&sessions, &http_req);
+    match profile_service::view_profile(&pool, viewer.as_deref(), profile, Lookup::Wallet).await {
+        Ok(Some(profile)) => HttpResponse::Ok().json(ProfileResponse::from(profile)),
+        Ok(None) => profile_not_found(),
+        Err(e) => privacy_error(e),
     }
 }
 
@@ -51,27 +85,47 @@ async fn get_or_create(
 #[get("/{wallet_address}")]
 async fn get_by_wallet(
     pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
     wallet: web::Path<String>,
 ) -> impl Responder {
-    match profile_service::get_profile_by_wallet(&pool, &wallet).await {
-        Ok(profile) => HttpResponse::Ok().json(ProfileResponse::from(profile)),
-        Err(_) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Profile not found"
-        })),
+    let viewer = viewer_wallet(&sessions, &req);
+    let profile = match profile_service::get_profile_by_wallet(&pool, &wallet).await {
+        Ok(profile) => profile,
+        Err(_) => return profile_not_found(),
+    };
+    match profile_service::view_profile(&pool, viewer.as_deref(), profile, Lookup::Wallet).await {
+        Ok(Some(profile)) => HttpResponse::Ok().json(ProfileResponse::from(profile)),
+        Ok(None) => profile_not_found(),
+        Err(e) => privacy_error(e),
     }
 }
 
-/// Get profile by username
+/// Get profile by username (old names redirect during the rename grace period)
 #[get("/username/{username}")]
 async fn get_by_username(
     pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
     username: web::Path<String>,
 ) -> impl Responder {
-    match profile_service::get_profile_by_username(&pool, &username).await {
-        Ok(profile) => HttpResponse::Ok().json(ProfileResponse::from(profile)),
-        Err(_) => HttpResponse::NotFound().json(serde_json::json!({
+    let not_found = || {
+        HttpResponse::NotFound().json(serde_json::json!({
             "error": "User not found"
-        })),
+        }))
+    };
+    let viewer = viewer_wallet(&sessions, &req);
+    let (profile, renamed_to) = match profile_service::resolve_username(&pool, &username).await {
+        Ok(resolved) => resolved,
+        Err(_) => return not_found(),
+    };
+    match profile_service::view_profile(&pool, viewer.as_deref(), profile, Lookup::Username).await {
+        Ok(Some(profile)) => HttpResponse::Ok().json(ProfileResponse {
+            renamed_to,
+            ..ProfileResponse::from(profile)
+        }),
+        Ok(None) => not_found(),
+        Err(e) => privacy_error(e),
     }
 }
 
@@ -79,13 +133,94 @@ async fn get_by_username(
 #[get("/inbox/{inbox_id}")]
 async fn get_by_inbox_id(
     pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
     inbox_id: web::Path<String>,
 ) -> impl Responder {
-    match profile_service::get_profile_by_inbox_id(&pool, &inbox_id).await {
-        Ok(profile) => HttpResponse::Ok().json(ProfileResponse::from(profile)),
-        Err(_) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Profile not found"
-        })),
+    let viewer = viewer_wallet(&sessions, &req);
+    let profile = match profile_service::get_profile_by_inbox_id(&pool, &inbox_id).await {
+        Ok(profile) => profile,
+        Err(_) => return profile_not_found(),
+    };
+    match profile_service::view_profile(&pool, viewer.as_deref(), profile, Lookup::InboxId).await {
+        Ok(Some(profile)) => HttpResponse::Ok().json(ProfileResponse::from(profile)),
+        Ok(None) => profile_not_found(),
+        Err(e) => privacy_error(e),
+    }
+}
+
+/// Look up many profiles by inbox id and/or wallet in one request (e.g. a chat list).
+/// Send the returned ETag as If-None-Match to get a 304 when nothing changed.
+#[post("/batch")]
+async fn get_batch(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    body: web::Json<BatchProfilesRequest>,
+) -> impl Responder {
+    let normalize = |ids: &[String], lowercase: bool| {
+        let mut ids: Vec<String> = ids
+            .iter()
+            .map(|id| if lowercase { id.trim().to_lowercase() } else { id.trim().to_string() })
+            .filter(|id| !id.is_empty())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    };
+    let inbox_ids = normalize(&body.inbox_ids, false);
+    let wallet_addresses = normalize(&body.wallet_addresses, true);
+    if inbox_ids.len() + wallet_addresses.len() > MAX_BATCH_SIZE {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("At most {} inbox ids and wallet addresses per batch", MAX_BATCH_SIZE)
+        }));
+    }
+
+    let viewer = viewer_wallet(&sessions, &req);
+    let lookup = profile_service::get_profiles_batch(&pool, viewer.as_deref(), &inbox_ids, &wallet_addresses);
+    let profiles = match lookup.await {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            log::error!("Batch profile lookup failed: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to get profiles"
+            }));
+        }
+    };
+
+    let response = BatchProfilesResponse {
+        profiles: profiles
+            .into_iter()
+            .map(|(key, profile)| (key, ProfileResponse::from(profile)))
+            .collect(),
+    };
+    // Keys are sorted, so equal content always serializes (and hashes) the same
+    let body = match serde_json::to_vec(&response) {
+        Ok(body) => body,
+        Err(e) => {
+            log::error!("Failed to serialize batch profiles: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to get profiles"
+            }));
+        }
+    };
+    let etag = EntityTag::new_strong(hex::encode(&Sha3_256::digest(&body)[..16]));
+
+    let unchanged = match req.get_header::<IfNoneMatch>() {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        None => false,
+    };
+    let mut builder = if unchanged { HttpResponse::NotModified() } else { HttpResponse::Ok() };
+    // What a viewer may see depends on their session
+    builder
+        .insert_header(header::ETag(etag))
+        .insert_header((header::CACHE_CONTROL, "private, no-cache"))
+        .insert_header((header::VARY, "Authorization"));
+    if unchanged {
+        builder.finish()
+    } else {
+        builder.content_type("application/json").body(body)
     }
 }
 
@@ -127,6 +262,8 @@ async fn update_profile(
 #[get("/search")]
 async fn search(
     pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
     query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
     let q = query.get("q").map(|s| s.as_str()).unwrap_or("");
@@ -141,8 +278,16 @@ async fn search(
         .and_then(|s| s.parse::<i64>().ok())
         .unwrap_or(10)
         .min(50); // Max 50 results
+    let offset = query
+        .get("offset")
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(0)
+        .max(0);
     
-    match profile_service::search_profiles(&pool, q, limit).await {
+    // Signed-in searcher, for blocks and privacy settings
+    let viewer = viewer_wallet(&sessions, &req);
+    
+    match profile_service::search_profiles(&pool, q, limit, offset, viewer.as_deref()).await {
         Ok(results) => HttpResponse::Ok().json(results),
         Err(e) => {
             log::error!("Search failed: {}", e);
@@ -170,13 +315,13 @@ async fn check_username(
         }));
     }
     
-    match profile_service::is_username_available(&pool, &username, wallet).await {
-        Ok(available) => {
+    match profile_service::username_unavailable_reason(&pool, &username, wallet).await {
+        Ok(reason) => {
             let mut response = serde_json::json!({
-                "available": available
+                "available": reason.is_none()
             });
-            if !available {
-                response["reason"] = serde_json::json!("Username is already taken");
+            if let Some(reason) = reason {
+                response["reason"] = serde_json::json!(reason);
             }
             HttpResponse::Ok().json(response)
         }
@@ -188,3 +333,386 @@ async fn check_username(
         }
     }
 }
+
+// ── Sign-in and privacy ──
+
+/// Get a nonce to sign in with, so profile reads can apply privacy settings for the viewer
+#[post("/auth/nonce")]
+async fn get_sign_in_nonce(
+    nonce_store: web::Data<NonceStore>,
+    req: web::Json<NonceRequest>,
+) -> impl Responder {
+    let nonce = admin_service::generate_nonce();
+    admin_service::store_nonce(&nonce_store, &req.wallet_address.to_lowercase(), nonce.clone());
+
+    HttpResponse::Ok().json(NonceResponse {
+        message: profile_service::sign_in_message(&nonce),
+        nonce,
+    })
+}
+
+/// Sign in with a signed nonce; send the token as `Authorization: Bearer <token>`
+#[post("/auth")]
+async fn sign_in(
+    sessions: web::Data<ProfileSessionStore>,
+    nonce_store: web::Data<NonceStore>,
+    req: web::Json<AuthRequest>,
+) -> impl Responder {
+    let wallet_address = req.wallet_address.to_lowercase();
+    let failed = || AuthResponse {
+        success: false,
+        session_token: None,
+        wallet_address: None,
+    };
+
+    if let Err(e) = admin_service::verify_nonce(&nonce_store, &wallet_address, &req.nonce) {
+        log::warn!("Sign-in nonce verification failed for {}: {}", wallet_address, e);
+        return HttpResponse::BadRequest().json(failed());
+    }
+
+    let message = profile_service::sign_in_message(&req.nonce);
+    match admin_service::verify_signature(&wallet_address, &message, &req.signature) {
+        Ok(true) => match admin_service::create_session(&sessions.0, &wallet_address) {
+            Ok(session_token) => HttpResponse::Ok().json(AuthResponse {
+                success: true,
+                session_token: Some(session_token),
+                wallet_address: Some(wallet_address),
+            }),
+            Err(e) => {
+                log::error!("Failed to create profile session: {}", e);
+                HttpResponse::InternalServerError().json(failed())
+            }
+        },
+        Ok(false) => HttpResponse::Unauthorized().json(failed()),
+        Err(e) => {
+            log::warn!("Sign-in signature verification error: {}", e);
+            HttpResponse::BadRequest().json(failed())
+        }
+    }
+}
+
+#[get("/me/privacy")]
+async fn get_privacy(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match profile_service::get_privacy_settings(&pool, &wallet_address).await {
+        Ok(settings) => HttpResponse::Ok().json(settings),
+        Err(e) => social_error(e, "get privacy settings"),
+    }
+}
+
+#[put("/me/privacy")]
+async fn update_privacy(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    body: web::Json<UpdatePrivacyRequest>,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match profile_service::update_privacy_settings(&pool, &wallet_address, body.into_inner()).await {
+        Ok(settings) => HttpResponse::Ok().json(settings),
+        Err(e) => social_error(e, "update privacy settings"),
+    }
+}
+
+/// Download everything stored about the signed-in wallet as JSON
+#[get("/me/export")]
+async fn export_account(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match account_service::export_account(&pool, &wallet_address).await {
+        Ok(export) => HttpResponse::Ok()
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"blocchat-export-{}.json\"", wallet_address),
+            ))
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .json(export),
+        Err(e) => {
+            log::error!("Account export failed for {}: {}", wallet_address, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to export account data"
+            }))
+        }
+    }
+}
+
+/// Delete the signed-in wallet's profile and personal data, keeping financial
+/// records, and end its sessions. 409 while its shops have open orders
+#[delete("/me")]
+async fn delete_account(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match account_service::delete_account(&pool, &wallet_address).await {
+        Ok(summary) => {
+            sessions
+                .0
+                .write()
+                .unwrap()
+                .retain(|_, session| session.wallet_address != wallet_address);
+            HttpResponse::Ok().json(summary)
+        }
+        // Open orders on the wallet's shops; anything else is a database failure
+        Err(e) if e.downcast_ref::<sqlx::Error>().is_none() => HttpResponse::Conflict().json(serde_json::json!({
+            "error": e.to_string()
+        })),
+        Err(e) => {
+            log::error!("Account deletion failed for {}: {}", wallet_address, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to delete account"
+            }))
+        }
+    }
+}
+
+/// Wallet of the signed-in viewer, if the request carries a valid profile session
+fn viewer_wallet(sessions: &ProfileSessionStore, req: &HttpRequest) -> Option<String> {
+    let token = req
+        .headers()
+        .get("Authorization")?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    admin_service::verify_session(&sessions.0, token).ok()
+}
+
+pub(crate) fn require_viewer(sessions: &ProfileSessionStore, req: &HttpRequest) -> Result<String, HttpResponse> {
+    viewer_wallet(sessions, req).ok_or_else(|| {
+        HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Sign in required"
+        }))
+    })
+}
+
+fn profile_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "error": "Profile not found"
+    }))
+}
+
+fn privacy_error(e: anyhow::Error) -> HttpResponse {
+    log::error!("Failed to apply privacy settings: {}", e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": "Failed to load profile"
+    }))
+}
+
+// ── Social graph ──
+
+/// Follow a profile as the signed-in wallet
+#[post("/{wallet_address}/follow")]
+async fn follow(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    target: web::Path<String>,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match social_service::follow(&pool, &wallet_address, &target).await {
+        Ok(relationship) => HttpResponse::Ok().json(relationship),
+        Err(e) => social_error(e, "follow"),
+    }
+}
+
+#[delete("/{wallet_address}/follow")]
+async fn unfollow(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    target: web::Path<String>,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match social_service::unfollow(&pool, &wallet_address, &target).await {
+        Ok(relationship) => HttpResponse::Ok().json(relationship),
+        Err(e) => social_error(e, "unfollow"),
+    }
+}
+
+/// Block a profile as the signed-in wallet; also removes follows between the two
+#[post("/{wallet_address}/block")]
+async fn block(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    target: web::Path<String>,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match social_service::block(&pool, &wallet_address, &target).await {
+        Ok(relationship) => HttpResponse::Ok().json(relationship),
+        Err(e) => social_error(e, "block"),
+    }
+}
+
+#[delete("/{wallet_address}/block")]
+async fn unblock(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    target: web::Path<String>,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match social_service::unblock(&pool, &wallet_address, &target).await {
+        Ok(relationship) => HttpResponse::Ok().json(relationship),
+        Err(e) => social_error(e, "unblock"),
+    }
+}
+
+#[get("/{wallet_address}/followers")]
+async fn get_followers(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    wallet: web::Path<String>,
+    query: web::Query<SocialListQuery>,
+) -> impl Responder {
+    let viewer = viewer_wallet(&sessions, &req);
+    match social_service::get_followers(&pool, &wallet, query.into_inner(), viewer.as_deref()).await {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(e) => social_error(e, "get followers"),
+    }
+}
+
+#[get("/{wallet_address}/following")]
+async fn get_following(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    wallet: web::Path<String>,
+    query: web::Query<SocialListQuery>,
+) -> impl Responder {
+    let viewer = viewer_wallet(&sessions, &req);
+    match social_service::get_following(&pool, &wallet, query.into_inner(), viewer.as_deref()).await {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(e) => social_error(e, "get following"),
+    }
+}
+
+/// Mutual follows
+#[get("/{wallet_address}/contacts")]
+async fn get_contacts(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    wallet: web::Path<String>,
+    query: web::Query<SocialListQuery>,
+) -> impl Responder {
+    let viewer = viewer_wallet(&sessions, &req);
+    match social_service::get_contacts(&pool, &wallet, query.into_inner(), viewer.as_deref()).await {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(e) => social_error(e, "get contacts"),
+    }
+}
+
+/// The signed-in wallet's own block list; nobody else may read it
+#[get("/{wallet_address}/blocked")]
+async fn get_blocked(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    wallet: web::Path<String>,
+    query: web::Query<SocialListQuery>,
+) -> impl Responder {
+    let viewer = match require_viewer(&sessions, &req) {
+        Ok(viewer) => viewer,
+        Err(response) => return response,
+    };
+    if !viewer.eq_ignore_ascii_case(&wallet) {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "You can only see your own blocked list"
+        }));
+    }
+    match social_service::get_blocked(&pool, &wallet, query.into_inner(), Some(&viewer)).await {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(e) => social_error(e, "get blocked users"),
+    }
+}
+
+/// Who to follow: friends of friends and followers not yet followed back
+#[get("/{wallet_address}/suggestions")]
+async fn get_suggestions(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    wallet: web::Path<String>,
+    query: web::Query<SocialListQuery>,
+) -> impl Responder {
+    let viewer = viewer_wallet(&sessions, &req);
+    match social_service::get_suggestions(&pool, &wallet, query.limit, viewer.as_deref()).await {
+        Ok(suggestions) => HttpResponse::Ok().json(suggestions),
+        Err(e) => social_error(e, "get suggestions"),
+    }
+}
+
+/// Follow and block state between two wallets; the signed-in wallet must be one of them
+#[get("/{wallet_address}/relationship/{other_wallet}")]
+async fn get_relationship(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let viewer = match require_viewer(&sessions, &req) {
+        Ok(viewer) => viewer,
+        Err(response) => return response,
+    };
+    let (wallet, other_wallet) = path.into_inner();
+    if !viewer.eq_ignore_ascii_case(&wallet) && !viewer.eq_ignore_ascii_case(&other_wallet) {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "You can only see your own relationships"
+        }));
+    }
+    match social_service::get_relationship(&pool, &wallet, &other_wallet).await {
+        Ok(relationship) => HttpResponse::Ok().json(relationship),
+        Err(e) => social_error(e, "get relationship"),
+    }
+}
+
+fn social_error(e: anyhow::Error, action: &str) -> HttpResponse {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::RowNotFound) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Profile not found"
+        })),
+        Some(_) => {
+            log::error!("Failed to {}: {}", action, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to {}", action)
+            }))
+        }
+        None => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": e.to_string()
+        })),
+    }
+}
@@ -1,6 +1,16 @@
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder, Scope};
-use crate::models::{CreateItemRequest, CreateShopRequest, UpdateItemRequest, UpdateShopRequest};
-use crate::services::shop_service;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder, Scope};
+use crate::handlers::profiles::require_viewer;
+use crate::models::{
+    AddLicenseCodesRequest, CreateItemRequest, CreateOrderRequest, CreateReviewRequest, CreateShopRequest, DeliveryNonceRequest,
+    DeliveryNonceResponse, DeliveryRequest, NonceStore, ProfileSessionStore,
+    PromotionRequest, RecordItemViewRequest, RefundOrderRequest, ReviewNonceRequest,
+    ReviewNonceResponse, ReviewReplyRequest, ReviewsQuery, ShopAnalyticsQuery,
+    SetDeliverableRequest, SetItemRulesRequest, UpdateItemRequest, UpdateShopRequest,
+};
+use crate::services::{
+    admin_service, analytics_service, delivery_service, item_rule_service, order_service,
+    promotion_service, review_service, shop_service,
+};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -98,6 +108,11 @@ async fn create_item(
 ) -> impl Responder {
     match shop_service::create_item(&pool, &shop_id, req.into_inner()).await {
         Ok(item) => HttpResponse::Created().json(item),
+        Err(sqlx::Error::Database(e)) if e.constraint().is_some() => {
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid item (stock must be >= 0, max_per_wallet > 0, sale must start before it ends)"
+            }))
+        }
         Err(e) => {
             eprintln!("Failed to create item: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -131,6 +146,11 @@ async fn update_item(
         Err(sqlx::Error::RowNotFound) => HttpResponse::NotFound().json(serde_json::json!({
             "error": "Item not found"
         })),
+        Err(sqlx::Error::Database(e)) if e.constraint().is_some() => {
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid item (stock must be >= 0, max_per_wallet > 0, sale must start before it ends)"
+            }))
+        }
         Err(e) => {
             eprintln!("Failed to update item: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -153,6 +173,468 @@ async fn delete_item(pool: web::Data<PgPool>, item_id: web::Path<Uuid>) -> impl
     }
 }
 
+// Order Endpoints
+
+/// Map an order service error: database errors are 404/500, anything else is a
+/// validation error reported back to the caller
+fn order_error(e: anyhow::Error, not_found: &str, action: &str) -> HttpResponse {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::RowNotFound) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": not_found
+        })),
+        Some(_) => {
+            eprintln!("Failed to {}: {}", action, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to {}", action)
+            }))
+        }
+        None => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": e.to_string()
+        })),
+    }
+}
+
+#[post("/shops/{shop_id}/orders")]
+async fn create_order(
+    pool: web::Data<PgPool>,
+    shop_id: web::Path<Uuid>,
+    req: web::Json<CreateOrderRequest>,
+) -> impl Responder {
+    match order_service::create_order(&pool, &shop_id, req.into_inner()).await {
+        Ok(order) => HttpResponse::Created().json(order),
+        Err(e) => order_error(e, "Shop not found", "create order"),
+    }
+}
+
+/// Orders of a shop; requires the shop owner's session
+#[get("/shops/{shop_id}/orders")]
+async fn get_shop_orders(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    shop_id: web::Path<Uuid>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match order_service::get_shop_orders(&pool, &shop_id, &seller_address).await {
+        Ok(orders) => HttpResponse::Ok().json(orders),
+        Err(e) => order_error(e, "Shop not found", "get orders"),
+    }
+}
+
+#[get("/orders/{order_id}")]
+async fn get_order(pool: web::Data<PgPool>, order_id: web::Path<Uuid>) -> impl Responder {
+    match order_service::get_order(&pool, &order_id).await {
+        Ok(order) => HttpResponse::Ok().json(order),
+        Err(e) => order_error(e, "Order not found", "get order"),
+    }
+}
+
+/// Order history of a wallet; requires that wallet's session
+#[get("/buyers/{wallet_address}/orders")]
+async fn get_buyer_orders(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    wallet_address: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = require_own_wallet(&sessions, &http_req, &wallet_address) {
+        return response;
+    }
+    match order_service::get_buyer_orders(&pool, &wallet_address).await {
+        Ok(orders) => HttpResponse::Ok().json(orders),
+        Err(e) => order_error(e, "Orders not found", "get orders"),
+    }
+}
+
+/// Sales of a wallet across its shops; requires that wallet's session
+#[get("/sellers/{wallet_address}/orders")]
+async fn get_seller_orders(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    wallet_address: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = require_own_wallet(&sessions, &http_req, &wallet_address) {
+        return response;
+    }
+    match order_service::get_seller_orders(&pool, &wallet_address).await {
+        Ok(orders) => HttpResponse::Ok().json(orders),
+        Err(e) => order_error(e, "Orders not found", "get orders"),
+    }
+}
+
+/// Signed-in wallet (see /api/profiles/auth), which must be `wallet_address`
+fn require_own_wallet(
+    sessions: &ProfileSessionStore,
+    req: &HttpRequest,
+    wallet_address: &str,
+) -> Result<String, HttpResponse> {
+    let wallet = require_viewer(sessions, req)?;
+    if !wallet.eq_ignore_ascii_case(wallet_address) {
+        return Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "You can only see your own orders"
+        })));
+    }
+    Ok(wallet)
+}
+
+#[post("/orders/{order_id}/check-payment")]
+async fn check_order_payment(pool: web::Data<PgPool>, order_id: web::Path<Uuid>) -> impl Responder {
+    match order_service::refresh_payment(&pool, &order_id).await {
+        Ok(order) => HttpResponse::Ok().json(order),
+        Err(e) => order_error(e, "Order not found", "check payment"),
+    }
+}
+
+/// Mark a paid order fulfilled; requires the seller's session
+#[post("/orders/{order_id}/fulfill")]
+async fn fulfill_order(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    order_id: web::Path<Uuid>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match order_service::fulfill_order(&pool, &order_id, &seller_address).await {
+        Ok(order) => HttpResponse::Ok().json(order),
+        Err(e) => order_error(e, "Order not found", "fulfill order"),
+    }
+}
+
+/// Record a refund for a paid order; requires the seller's session
+#[post("/orders/{order_id}/refund")]
+async fn refund_order(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    order_id: web::Path<Uuid>,
+    req: web::Json<RefundOrderRequest>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match order_service::refund_order(&pool, &order_id, &seller_address, req.into_inner()).await {
+        Ok(order) => HttpResponse::Ok().json(order),
+        Err(e) => order_error(e, "Order not found", "refund order"),
+    }
+}
+
+// Promotion Endpoints (all require the shop owner's session)
+
+#[post("/shops/{shop_id}/promotions")]
+async fn create_promotion(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    shop_id: web::Path<Uuid>,
+    req: web::Json<PromotionRequest>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match promotion_service::create_promotion(&pool, &shop_id, &seller_address, req.into_inner()).await {
+        Ok(promotion) => HttpResponse::Created().json(promotion),
+        Err(e) => promotion_error(e, "create promotion"),
+    }
+}
+
+#[get("/shops/{shop_id}/promotions")]
+async fn get_promotions(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    shop_id: web::Path<Uuid>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match promotion_service::get_promotions(&pool, &shop_id, &seller_address).await {
+        Ok(promotions) => HttpResponse::Ok().json(promotions),
+        Err(e) => promotion_error(e, "get promotions"),
+    }
+}
+
+#[put("/shops/{shop_id}/promotions/{promotion_id}")]
+async fn update_promotion(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+    req: web::Json<PromotionRequest>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    let (shop_id, promotion_id) = path.into_inner();
+    match promotion_service::update_promotion(&pool, &shop_id, &promotion_id, &seller_address, req.into_inner()).await {
+        Ok(promotion) => HttpResponse::Ok().json(promotion),
+        Err(e) => promotion_error(e, "update promotion"),
+    }
+}
+
+#[delete("/shops/{shop_id}/promotions/{promotion_id}")]
+async fn delete_promotion(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    let (shop_id, promotion_id) = path.into_inner();
+    match promotion_service::delete_promotion(&pool, &shop_id, &promotion_id, &seller_address).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => promotion_error(e, "delete promotion"),
+    }
+}
+
+#[get("/shops/{shop_id}/promotions/{promotion_id}/redemptions")]
+async fn get_promotion_redemptions(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    let (shop_id, promotion_id) = path.into_inner();
+    match promotion_service::get_redemptions(&pool, &shop_id, &promotion_id, &seller_address).await {
+        Ok(redemptions) => HttpResponse::Ok().json(redemptions),
+        Err(e) => promotion_error(e, "get promotion redemptions"),
+    }
+}
+
+/// Duplicate codes and constraint violations are the seller's input, not server errors
+fn promotion_error(e: anyhow::Error, action: &str) -> HttpResponse {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(db)) if db.is_unique_violation() => HttpResponse::Conflict()
+            .json(serde_json::json!({ "error": "This shop already has a promotion with that code" })),
+        Some(sqlx::Error::Database(db)) if db.constraint().is_some() => HttpResponse::BadRequest()
+            .json(serde_json::json!({ "error": "Invalid promotion" })),
+        _ => order_error(e, "Promotion not found", action),
+    }
+}
+
+// Analytics Endpoints
+
+/// Record that an item was viewed, for conversion stats
+#[post("/items/{item_id}/views")]
+async fn record_item_view(
+    pool: web::Data<PgPool>,
+    item_id: web::Path<Uuid>,
+    req: web::Json<RecordItemViewRequest>,
+) -> impl Responder {
+    match analytics_service::record_item_view(&pool, &item_id, req.into_inner()).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => order_error(e, "Item not found", "record item view"),
+    }
+}
+
+/// Sales and conversion stats; requires the shop owner's session
+#[get("/shops/{shop_id}/analytics")]
+async fn get_shop_analytics(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    shop_id: web::Path<Uuid>,
+    query: web::Query<ShopAnalyticsQuery>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match analytics_service::get_shop_analytics(&pool, &shop_id, &seller_address, query.into_inner()).await {
+        Ok(analytics) => HttpResponse::Ok().json(analytics),
+        Err(e) => order_error(e, "Shop not found", "get shop analytics"),
+    }
+}
+
+// Review Endpoints
+
+/// Get a nonce and the message the buyer must sign to review an item from an order
+#[post("/items/{item_id}/reviews/nonce")]
+async fn get_review_nonce(
+    nonce_store: web::Data<NonceStore>,
+    item_id: web::Path<Uuid>,
+    req: web::Json<ReviewNonceRequest>,
+) -> impl Responder {
+    let nonce = admin_service::generate_nonce();
+    let message = review_service::review_message(&item_id, &req.order_id, &nonce);
+    admin_service::store_nonce(&nonce_store, &req.wallet_address, nonce.clone());
+
+    HttpResponse::Ok().json(ReviewNonceResponse { nonce, message })
+}
+
+#[post("/items/{item_id}/reviews")]
+async fn create_review(
+    pool: web::Data<PgPool>,
+    nonce_store: web::Data<NonceStore>,
+    item_id: web::Path<Uuid>,
+    req: web::Json<CreateReviewRequest>,
+) -> impl Responder {
+    match review_service::create_review(&pool, &nonce_store, &item_id, req.into_inner()).await {
+        Ok(review) => HttpResponse::Created().json(review),
+        Err(e) => order_error(e, "Order not found", "create review"),
+    }
+}
+
+#[get("/items/{item_id}/reviews")]
+async fn get_item_reviews(
+    pool: web::Data<PgPool>,
+    item_id: web::Path<Uuid>,
+    query: web::Query<ReviewsQuery>,
+) -> impl Responder {
+    match review_service::get_item_reviews(&pool, &item_id, query.limit, query.offset).await {
+        Ok(reviews) => HttpResponse::Ok().json(reviews),
+        Err(e) => order_error(e, "Item not found", "get reviews"),
+    }
+}
+
+/// Reply to a review as the seller; requires the shop owner's session
+#[post("/reviews/{review_id}/reply")]
+async fn reply_to_review(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    review_id: web::Path<Uuid>,
+    req: web::Json<ReviewReplyRequest>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match review_service::reply_to_review(&pool, &review_id, &seller_address, req.into_inner()).await {
+        Ok(review) => HttpResponse::Ok().json(review),
+        Err(e) => order_error(e, "Review not found", "reply to review"),
+    }
+}
+
+// Holder Rule Endpoints
+
+/// Replace an item's holder gate and discount tiers; requires the shop owner's session
+#[put("/items/{item_id}/rules")]
+async fn set_item_rules(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    item_id: web::Path<Uuid>,
+    req: web::Json<SetItemRulesRequest>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match item_rule_service::set_item_rules(&pool, &item_id, &seller_address, req.into_inner()).await {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(e) => order_error(e, "Item not found", "set item rules"),
+    }
+}
+
+#[get("/items/{item_id}/rules")]
+async fn get_item_rules(
+    pool: web::Data<PgPool>,
+    item_id: web::Path<Uuid>,
+) -> impl Responder {
+    match item_rule_service::get_item_rules(&pool, &item_id).await {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(e) => order_error(e, "Item not found", "get item rules"),
+    }
+}
+
+/// Whether a wallet may buy an item and which discount it would get
+#[get("/items/{item_id}/eligibility/{wallet}")]
+async fn check_item_eligibility(
+    pool: web::Data<PgPool>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (item_id, wallet) = path.into_inner();
+    match item_rule_service::check_eligibility(&pool, &item_id, &wallet).await {
+        Ok(eligibility) => HttpResponse::Ok().json(eligibility),
+        Err(e) => order_error(e, "Item not found", "check item eligibility"),
+    }
+}
+
+// Digital Delivery Endpoints
+
+/// Set an item's encrypted deliverable; requires the shop owner's session
+#[put("/items/{item_id}/deliverable")]
+async fn set_deliverable(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    item_id: web::Path<Uuid>,
+    req: web::Json<SetDeliverableRequest>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match delivery_service::set_deliverable(&pool, &item_id, &seller_address, req.into_inner()).await {
+        Ok(item) => HttpResponse::Ok().json(item),
+        Err(e) => order_error(e, "Item not found", "set deliverable"),
+    }
+}
+
+/// Add license codes to an item's pool; requires the shop owner's session
+#[post("/items/{item_id}/license-codes")]
+async fn add_license_codes(
+    pool: web::Data<PgPool>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    item_id: web::Path<Uuid>,
+    req: web::Json<AddLicenseCodesRequest>,
+) -> impl Responder {
+    let seller_address = match require_viewer(&sessions, &http_req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+    match delivery_service::add_license_codes(&pool, &item_id, &seller_address, req.into_inner()).await {
+        Ok(result) => HttpResponse::Created().json(result),
+        Err(e) => order_error(e, "Item not found", "add license codes"),
+    }
+}
+
+/// Get a nonce and the message the buyer must sign to unlock an order
+#[post("/orders/{order_id}/delivery/nonce")]
+async fn get_delivery_nonce(
+    nonce_store: web::Data<NonceStore>,
+    order_id: web::Path<Uuid>,
+    req: web::Json<DeliveryNonceRequest>,
+) -> impl Responder {
+    let nonce = admin_service::generate_nonce();
+    let message = delivery_service::delivery_message(&order_id, &nonce);
+    admin_service::store_nonce(&nonce_store, &req.wallet_address, nonce.clone());
+
+    HttpResponse::Ok().json(DeliveryNonceResponse { nonce, message })
+}
+
+#[post("/orders/{order_id}/delivery")]
+async fn get_delivery(
+    pool: web::Data<PgPool>,
+    nonce_store: web::Data<NonceStore>,
+    order_id: web::Path<Uuid>,
+    req: web::Json<DeliveryRequest>,
+) -> impl Responder {
+    match delivery_service::deliver_order(&pool, &nonce_store, &order_id, req.into_inner()).await {
+        Ok(delivery) => HttpResponse::Ok().json(delivery),
+        Err(e) => order_error(e, "Order not found", "deliver order"),
+    }
+}
+
 pub fn configure() -> Scope {
     web::scope("/shops")
         .service(create_shop)
@@ -164,4 +646,30 @@ pub fn configure() -> Scope {
         .service(get_items)
         .service(update_item)
         .service(delete_item)
+        .service(create_order)
+        .service(get_shop_orders)
+        .service(get_order)
+        .service(get_buyer_orders)
+        .service(get_seller_orders)
+        .service(check_order_payment)
+        .service(fulfill_order)
+        .service(refund_order)
+        .service(set_deliverable)
+        .service(add_license_codes)
+        .service(create_promotion)
+        .service(get_promotions)
+        .service(update_promotion)
+        .service(delete_promotion)
+        .service(get_promotion_redemptions)
+        .service(record_item_view)
+        .service(get_shop_analytics)
+        .service(get_review_nonce)
+        .service(create_review)
+        .service(get_item_reviews)
+        .service(reply_to_review)
+        .service(set_item_rules)
+        .service(get_item_rules)
+        .service(check_item_eligibility)
+        .service(get_delivery_nonce)
+        .service(get_delivery)
 }
@@ -1,8 +1,13 @@
-use actix_web::{delete, get, post, web, HttpResponse, Responder, Scope};
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder, Scope};
 use crate::{
     db::DbPool,
-    models::{CreateTokenGateRequest, VerifyTokenGateRequest},
-    services::token_gate_service,
+    handlers::{admin::require_admin, profiles::require_viewer},
+    models::{
+        CreateTokenGateRequest, ProfileSessionStore, ReverifySettingsResponse, SessionStore,
+        SimulateTokenGateRequest, SyncGateMembersRequest, UpdateReverifySettingsRequest,
+        VerifyGatePassRequest, VerifyGatePassResponse, VerifyTokenGateRequest,
+    },
+    services::{gate_pass_service, gate_pass_service::GatePassKeys, gate_reverifier, group_service, token_gate_service},
 };
 
 pub fn configure() -> Scope {
@@ -11,6 +16,14 @@ pub fn configure() -> Scope {
         .service(get_gates)
         .service(delete_gates)
         .service(verify_gates)
+        .service(simulate_gates)
+        .service(get_pass_public_key)
+        .service(verify_pass)
+        .service(sync_members)
+        .service(remove_member)
+        .service(get_evictions)
+        .service(get_reverify_settings)
+        .service(update_reverify_settings)
 }
 
 #[post("/conversations/{conversation_id}")]
@@ -23,6 +36,11 @@ async fn create_or_update_gates(
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({
             "message": "Token gates created successfully"
         })),
+        Err(sqlx::Error::Database(e)) if e.constraint().is_some() => {
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid token gate requirement (check requirement_type, min_hold_days 1-365 with a token_address, min_profile_age_days 1-3650 and eas_schema_uid)"
+            }))
+        }
         Err(e) => {
             log::error!("Failed to create token gates: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -72,10 +90,55 @@ async fn delete_gates(
 #[post("/verify")]
 async fn verify_gates(
     pool: web::Data<DbPool>,
+    gate_pass_keys: web::Data<Option<GatePassKeys>>,
     req: web::Json<VerifyTokenGateRequest>,
 ) -> impl Responder {
-    match token_gate_service::verify_token_gates(&pool, req.into_inner()).await {
-        Ok(response) => HttpResponse::Ok().json(response),
+    let req = req.into_inner();
+    let conversation_id = req.conversation_id.clone();
+    let wallet_address = req.wallet_address.clone();
+    let include_pass = req.include_pass;
+
+    match token_gate_service::verify_token_gates(&pool, req).await {
+        Ok(mut response) => {
+            // A passing check on a gated conversation admits the wallet as a known member
+            if response.allowed && !response.requirements_met.is_empty() {
+                if let Err(e) = token_gate_service::record_membership_check(
+                    &pool,
+                    &conversation_id,
+                    &wallet_address,
+                    true,
+                    &response.requirements_met,
+                )
+                .await
+                {
+                    log::error!("Failed to record gate membership: {}", e);
+                }
+            }
+
+            if include_pass && response.allowed {
+                match (gate_pass_keys.as_ref(), &response.gate_hash, response.block_number) {
+                    (Some(keys), Some(gate_hash), Some(block_number)) => {
+                        match gate_pass_service::issue_pass(
+                            keys,
+                            &conversation_id,
+                            &wallet_address,
+                            gate_hash,
+                            block_number,
+                        ) {
+                            Ok((token, expires_at)) => {
+                                response.gate_pass = Some(token);
+                                response.gate_pass_expires_at = Some(expires_at.to_rfc3339());
+                            }
+                            Err(e) => log::error!("Failed to sign gate pass: {}", e),
+                        }
+                    }
+                    (None, _, _) => log::warn!("Gate pass requested but no signing key is loaded"),
+                    // Ungated conversation: nothing to attest
+                    _ => {}
+                }
+            }
+            HttpResponse::Ok().json(response)
+        }
         Err(e) => {
             log::error!("Failed to verify token gates: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -84,3 +147,256 @@ async fn verify_gates(
         }
     }
 }
+
+/// Dry-run a draft or the saved gate against a list of wallets (nothing is persisted)
+#[post("/conversations/{conversation_id}/simulate")]
+async fn simulate_gates(
+    pool: web::Data<DbPool>,
+    conversation_id: web::Path<String>,
+    req: web::Json<SimulateTokenGateRequest>,
+) -> impl Responder {
+    if req.wallet_addresses.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "wallet_addresses is required"
+        }));
+    }
+    if req.wallet_addresses.len() > token_gate_service::MAX_SIMULATION_WALLETS {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("At most {} wallets can be simulated at once", token_gate_service::MAX_SIMULATION_WALLETS)
+        }));
+    }
+
+    match token_gate_service::simulate_token_gates(&pool, &conversation_id, req.into_inner()).await {
+        Ok(Some(response)) => HttpResponse::Ok().json(response),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "No token gates found for this conversation"
+        })),
+        Err(e) => {
+            log::error!("Failed to simulate token gates: {}", e);
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Failed to simulate token gates: {}", e)
+            }))
+        }
+    }
+}
+
+// ── Gate passes ──
+
+/// Public key for verifying gate passes offline (PEM + JWK)
+#[get("/pass/public-key")]
+async fn get_pass_public_key(gate_pass_keys: web::Data<Option<GatePassKeys>>) -> impl Responder {
+    match gate_pass_keys.as_ref() {
+        Some(keys) => HttpResponse::Ok().json(gate_pass_service::public_key_response(keys)),
+        None => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Gate passes are not enabled"
+        })),
+    }
+}
+
+/// Check a gate pass: signature, expiry, optional subject match, and that the
+/// conversation's gate has not changed since the pass was issued
+#[post("/pass/verify")]
+async fn verify_pass(
+    pool: web::Data<DbPool>,
+    gate_pass_keys: web::Data<Option<GatePassKeys>>,
+    req: web::Json<VerifyGatePassRequest>,
+) -> impl Responder {
+    let keys = match gate_pass_keys.as_ref() {
+        Some(k) => k,
+        None => return HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Gate passes are not enabled"
+        })),
+    };
+
+    let invalid = |reason: String| VerifyGatePassResponse {
+        valid: false,
+        reason: Some(reason),
+        claims: None,
+    };
+
+    let claims = match gate_pass_service::decode_pass(keys, &req.gate_pass) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::Ok().json(invalid(format!("Invalid gate pass: {}", e))),
+    };
+
+    if let Some(conversation_id) = &req.conversation_id {
+        if &claims.conversation_id != conversation_id {
+            return HttpResponse::Ok().json(invalid("Pass was issued for another conversation".to_string()));
+        }
+    }
+    if let Some(wallet_address) = &req.wallet_address {
+        if claims.sub != wallet_address.to_lowercase() {
+            return HttpResponse::Ok().json(invalid("Pass was issued for another wallet".to_string()));
+        }
+    }
+
+    match token_gate_service::get_gate_hash(&pool, &claims.conversation_id).await {
+        Ok(Some(current)) if current == claims.gate_hash => {
+            HttpResponse::Ok().json(VerifyGatePassResponse {
+                valid: true,
+                reason: None,
+                claims: Some(claims),
+            })
+        }
+        Ok(_) => HttpResponse::Ok().json(invalid("Gate requirements changed since the pass was issued".to_string())),
+        Err(e) => {
+            log::error!("Failed to load gate for pass verification: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to verify gate pass"
+            }))
+        }
+    }
+}
+
+// ── Membership re-verification ──
+
+#[put("/conversations/{conversation_id}/members")]
+async fn sync_members(
+    pool: web::Data<DbPool>,
+    admin_sessions: web::Data<SessionStore>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    conversation_id: web::Path<String>,
+    req: web::Json<SyncGateMembersRequest>,
+) -> impl Responder {
+    if let Err(response) = require_group_manager(&pool, &admin_sessions, &sessions, &http_req, &conversation_id).await {
+        return response;
+    }
+    match token_gate_service::sync_members(&pool, &conversation_id, &req.wallet_addresses).await {
+        Ok(count) => HttpResponse::Ok().json(serde_json::json!({
+            "members": count
+        })),
+        Err(e) => {
+            log::error!("Failed to sync gate members: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to sync members"
+            }))
+        }
+    }
+}
+
+#[delete("/conversations/{conversation_id}/members/{wallet_address}")]
+async fn remove_member(
+    pool: web::Data<DbPool>,
+    admin_sessions: web::Data<SessionStore>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (conversation_id, wallet_address) = path.into_inner();
+    if let Err(response) = require_group_manager(&pool, &admin_sessions, &sessions, &req, &conversation_id).await {
+        return response;
+    }
+    match token_gate_service::remove_member(&pool, &conversation_id, &wallet_address).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            log::error!("Failed to remove gate member: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to remove member"
+            }))
+        }
+    }
+}
+
+/// Members who no longer meet the gate and are past the grace period
+#[get("/conversations/{conversation_id}/evictions")]
+async fn get_evictions(
+    pool: web::Data<DbPool>,
+    admin_sessions: web::Data<SessionStore>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    conversation_id: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = require_group_manager(&pool, &admin_sessions, &sessions, &req, &conversation_id).await {
+        return response;
+    }
+    match token_gate_service::get_eviction_report(&pool, &conversation_id, false).await {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => {
+            log::error!("Failed to get gate evictions: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to get evictions"
+            }))
+        }
+    }
+}
+
+/// Member lists, evictions and the webhook that receives member wallets can only be
+/// read or changed by an admin or the group's owner (the owner of its public listing)
+async fn require_group_manager(
+    pool: &DbPool,
+    admin_sessions: &SessionStore,
+    sessions: &ProfileSessionStore,
+    req: &HttpRequest,
+    conversation_id: &str,
+) -> Result<(), HttpResponse> {
+    if require_admin(admin_sessions, req).is_ok() {
+        return Ok(());
+    }
+    let wallet_address = require_viewer(sessions, req)?;
+    match group_service::get_group(pool, conversation_id).await {
+        Ok(group) if group.owner_wallet.eq_ignore_ascii_case(&wallet_address) => Ok(()),
+        Ok(_) | Err(sqlx::Error::RowNotFound) => Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only the group owner or an admin can manage gate membership"
+        }))),
+        Err(e) => {
+            log::error!("Failed to load group owner: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to check group owner"
+            })))
+        }
+    }
+}
+
+#[get("/conversations/{conversation_id}/reverify-settings")]
+async fn get_reverify_settings(
+    pool: web::Data<DbPool>,
+    admin_sessions: web::Data<SessionStore>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    conversation_id: web::Path<String>,
+) -> impl Responder {
+    if let Err(response) = require_group_manager(&pool, &admin_sessions, &sessions, &req, &conversation_id).await {
+        return response;
+    }
+    match token_gate_service::get_reverify_settings(&pool, &conversation_id).await {
+        Ok(settings) => HttpResponse::Ok().json(settings),
+        Err(e) => {
+            log::error!("Failed to get re-verification settings: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to get re-verification settings"
+            }))
+        }
+    }
+}
+
+#[put("/conversations/{conversation_id}/reverify-settings")]
+async fn update_reverify_settings(
+    pool: web::Data<DbPool>,
+    admin_sessions: web::Data<SessionStore>,
+    sessions: web::Data<ProfileSessionStore>,
+    http_req: HttpRequest,
+    conversation_id: web::Path<String>,
+    req: web::Json<UpdateReverifySettingsRequest>,
+) -> impl Responder {
+    if let Err(response) = require_group_manager(&pool, &admin_sessions, &sessions, &http_req, &conversation_id).await {
+        return response;
+    }
+    if let Some(url) = req.webhook_url.as_deref().filter(|url| !url.trim().is_empty()) {
+        if let Err(e) = gate_reverifier::check_webhook_url(url).await {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": e.to_string()
+            }));
+        }
+    }
+
+    match token_gate_service::update_reverify_settings(&pool, &conversation_id, req.into_inner()).await {
+        Ok(settings) => HttpResponse::Ok().json(ReverifySettingsResponse::from(settings)),
+        Err(e) => {
+            log::error!("Failed to update re-verification settings: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to update re-verification settings"
+            }))
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use actix_web::{get, post, web, HttpResponse, Responder, Scope};
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::collections::HashMap;
 use std::sync::RwLock;
 use std::time::Instant;
 
+use crate::services::social_service;
+
 /// Shared in-memory store: conversation_id -> (inbox_id -> last_typed_at)
 pub type TypingStore = RwLock<HashMap<String, HashMap<String, Instant>>>;
 
@@ -15,6 +18,12 @@ pub struct TypingRequest {
     pub inbox_id: String,
 }
 
+#[derive(Deserialize)]
+pub struct TypingQuery {
+    /// Viewer's inbox_id; users they blocked or that blocked them are left out
+    pub viewer_inbox_id: Option<String>,
+}
+
 #[derive(Serialize)]
 struct TypingResponse {
     inbox_ids: Vec<String>,
@@ -41,11 +50,14 @@ async fn post_typing(
 }
 
 /// GET /conversations/{conversation_id}/typing
-/// Return the list of inbox_ids currently typing (not expired).
+/// Return the list of inbox_ids currently typing (not expired), minus anyone
+/// blocked either way by `?viewer_inbox_id=`.
 #[get("/conversations/{conversation_id}/typing")]
 async fn get_typing(
+    pool: web::Data<PgPool>,
     store: web::Data<TypingStore>,
     conversation_id: web::Path<String>,
+    query: web::Query<TypingQuery>,
 ) -> impl Responder {
     let conv_id = conversation_id.into_inner();
     let expiry = std::time::Duration::from_secs(TYPING_EXPIRY_SECS);
@@ -60,6 +72,15 @@ async fn get_typing(
         }
     }
 
+    if let Some(viewer) = &query.viewer_inbox_id {
+        if !active.is_empty() {
+            match social_service::hidden_inbox_ids(&pool, viewer).await {
+                Ok(hidden) => active.retain(|inbox_id| !hidden.contains(inbox_id)),
+                Err(e) => log::error!("Failed to load blocks for typing filter: {}", e),
+            }
+        }
+    }
+
     HttpResponse::Ok().json(TypingResponse { inbox_ids: active })
 }
 
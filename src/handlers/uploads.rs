@@ -0,0 +1,109 @@
+use actix_multipart::Multipart;
+use actix_web::{get, http::header, post, web, HttpRequest, HttpResponse, Responder, Scope};
+use futures_util::TryStreamExt;
+
+use crate::handlers::profiles::require_viewer;
+use crate::models::ProfileSessionStore;
+use crate::services::image_service::{self, ImageStorage};
+
+pub fn configure() -> Scope {
+    web::scope("/uploads")
+        .service(upload_image)
+        .service(get_image)
+}
+
+/// Upload an avatar or group image as the multipart field `file`. Requires a profile
+/// session. Returns the URL to save as `avatar_url` / `image_url` plus thumbnails.
+#[post("/images")]
+async fn upload_image(
+    storage: web::Data<dyn ImageStorage>,
+    sessions: web::Data<ProfileSessionStore>,
+    req: HttpRequest,
+    mut payload: Multipart,
+) -> impl Responder {
+    let wallet_address = match require_viewer(&sessions, &req) {
+        Ok(wallet) => wallet,
+        Err(response) => return response,
+    };
+
+    let data = match read_file_field(&mut payload).await {
+        Ok(Some(data)) => data,
+        Ok(None) => return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Multipart field 'file' is required"
+        })),
+        Err(response) => return response,
+    };
+
+    match image_service::upload_image(storage.get_ref(), data).await {
+        Ok(image) => {
+            log::info!("Image uploaded by {}: {}", wallet_address, image.url);
+            HttpResponse::Created().json(image)
+        }
+        Err(e) if e.downcast_ref::<std::io::Error>().is_some() => {
+            log::error!("Failed to store image: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to store image"
+            }))
+        }
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": e.to_string()
+        })),
+    }
+}
+
+/// Read the `file` field, stopping as soon as it goes over the size limit
+async fn read_file_field(payload: &mut Multipart) -> Result<Option<Vec<u8>>, HttpResponse> {
+    let bad_request = |e: actix_multipart::MultipartError| {
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid multipart body: {}", e)
+        }))
+    };
+
+    while let Some(mut field) = payload.try_next().await.map_err(bad_request)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let mut data = Vec::new();
+        while let Some(chunk) = field.try_next().await.map_err(bad_request)? {
+            if data.len() + chunk.len() > image_service::MAX_UPLOAD_BYTES {
+                return Err(HttpResponse::PayloadTooLarge().json(serde_json::json!({
+                    "error": format!("Images can be at most {} MB", image_service::MAX_UPLOAD_BYTES / 1024 / 1024)
+                })));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        return Ok(Some(data));
+    }
+    Ok(None)
+}
+
+/// Serve a stored image. Keys are content addressed, so responses are cached forever.
+#[get("/{key:images/.+}")]
+async fn get_image(
+    storage: web::Data<dyn ImageStorage>,
+    key: web::Path<String>,
+) -> impl Responder {
+    let not_found = || {
+        HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Image not found"
+        }))
+    };
+    if !image_service::is_valid_key(&key) {
+        return not_found();
+    }
+
+    match storage.get(&key).await {
+        Ok(Some(bytes)) => HttpResponse::Ok()
+            .content_type(image_service::content_type_for(&key))
+            .insert_header((header::CACHE_CONTROL, image_service::CACHE_CONTROL))
+            .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+            .body(bytes),
+        Ok(None) => not_found(),
+        Err(e) => {
+            log::error!("Failed to read image {}: {}", key, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to read image"
+            }))
+        }
+    }
+}
@@ -11,7 +11,7 @@ mod services;
 mod db;
 mod middleware;
 
-use models::{NonceStore, SessionStore};
+use models::{NonceStore, ProfileSessionStore, SessionStore};
 use handlers::typing::TypingStore;
 
 #[actix_web::main]
@@ -41,16 +41,49 @@ async fn main() -> std::io::Result<()> {
     // Spawn Alpha Bot event watcher background task
     let base_rpc_url = env::var("BASE_RPC_URL")
         .unwrap_or_else(|_| "https://mainnet.base.org".to_string());
-    services::event_watcher::spawn(db_pool.clone(), base_rpc_url);
+    services::event_watcher::spawn(db_pool.clone(), base_rpc_url.clone());
     services::feed_poller::spawn(db_pool.clone());
+    services::gate_reverifier::spawn(db_pool.clone());
+    services::basename_refresher::spawn(db_pool.clone());
+    services::order_watcher::spawn(db_pool.clone(), base_rpc_url);
     
     // Initialize session, nonce, and typing stores
     let session_store: SessionStore = Arc::new(RwLock::new(HashMap::new()));
+    let profile_sessions = ProfileSessionStore::default();
     let nonce_store: NonceStore = Arc::new(RwLock::new(HashMap::new()));
     let typing_store = web::Data::new(TypingStore::new(HashMap::new()));
     
     log::info!("✓ Session, nonce, and typing stores initialized");
     
+    // Load the key used to sign token gate passes. The committed blocchat-key.pem is
+    // public, so only debug builds fall back to it
+    let gate_pass_key_path = match env::var("GATE_PASS_KEY_PATH").ok().filter(|p| !p.is_empty()) {
+        Some(path) => Some(path),
+        None if cfg!(debug_assertions) => Some("blocchat-key.pem".to_string()),
+        None => None,
+    };
+    let gate_pass_keys = match gate_pass_key_path.as_deref().map(services::gate_pass_service::load_keys) {
+        Some(Ok(keys)) => {
+            log::info!("✓ Gate pass signing key loaded");
+            Some(keys)
+        }
+        Some(Err(e)) => {
+            log::warn!("Gate passes disabled: {}", e);
+            None
+        }
+        None => {
+            log::error!("GATE_PASS_KEY_PATH is not set; gate passes disabled");
+            None
+        }
+    };
+    let gate_pass_keys = web::Data::new(gate_pass_keys);
+    
+    // Storage for uploaded avatars and group images
+    let image_storage = web::Data::from(
+        services::image_service::storage_from_env().expect("Failed to initialize image storage"),
+    );
+    log::info!("✓ Image storage initialized");
+    
     // Get CORS origins
     let cors_origins = env::var("CORS_ALLOWED_ORIGINS")
         .unwrap_or_else(|_| "http://localhost:5173".to_string());
@@ -75,8 +108,11 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .app_data(web::Data::new(db_pool.clone()))
             .app_data(web::Data::new(session_store.clone()))
+            .app_data(web::Data::new(profile_sessions.clone()))
             .app_data(web::Data::new(nonce_store.clone()))
             .app_data(typing_store.clone())
+            .app_data(gate_pass_keys.clone())
+            .app_data(image_storage.clone())
             .wrap(Logger::default())
             .wrap(cors)
             .service(
@@ -87,11 +123,13 @@ async fn main() -> std::io::Result<()> {
                     .service(handlers::payments::configure())
                     .service(handlers::token_gates::configure())
                     .service(handlers::shops::configure())
+                    .service(handlers::marketplace::configure())
                     .service(handlers::typing::configure())
                     .service(handlers::groups::configure())
                     .service(handlers::alpha_bot::configure())
                     .service(handlers::ai::configure())
                     .service(handlers::feeds::configure())
+                    .service(handlers::uploads::configure())
             )
     })
     .bind(&bind_address)?
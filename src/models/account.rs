@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use crate::models::alpha_bot::AlphaBotConfig;
+use crate::models::feed::FeedSubscription;
+use crate::models::{ItemResponse, Order, OrderItem, PublicGroup, Shop, ShopItemReview, Transaction, UserProfile};
+
+/// Everything stored about a wallet, for GET /profiles/me/export
+#[derive(Debug, Serialize)]
+pub struct AccountExport {
+    pub wallet_address: String,
+    pub exported_at: DateTime<Utc>,
+    pub profile: Option<UserProfile>,
+    pub username_history: Vec<UsernameChange>,
+    pub following: Vec<String>,
+    pub followers: Vec<String>,
+    pub blocked: Vec<String>,
+    pub transactions: Vec<Transaction>, // Sent or received
+    pub orders: Vec<ExportedOrder>,     // Placed as buyer
+    pub reviews: Vec<ShopItemReview>,
+    pub shops: Vec<ExportedShop>,
+    pub public_groups: Vec<PublicGroup>,
+    pub feed_subscriptions: Vec<FeedSubscription>,
+    pub alpha_bot_configs: Vec<AlphaBotConfig>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct UsernameChange {
+    pub old_username: String,
+    pub new_username: String,
+    pub changed_at: DateTime<Utc>,
+    pub locked_until: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedOrder {
+    #[serde(flatten)]
+    pub order: Order,
+    pub items: Vec<OrderItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedShop {
+    #[serde(flatten)]
+    pub shop: Shop,
+    pub items: Vec<ItemResponse>,
+}
+
+/// What DELETE /profiles/me removed. Payments and orders are kept as financial records.
+#[derive(Debug, Serialize)]
+pub struct AccountDeletionSummary {
+    pub wallet_address: String,
+    pub profile_deleted: bool,
+    pub username_history_deleted: u64,
+    pub reviews_deleted: u64,
+    pub shops_deleted: u64,
+    pub public_groups_deleted: u64,
+    pub feed_subscriptions_deleted: u64,
+    pub alpha_bot_configs_deleted: u64,
+    pub gate_memberships_deleted: u64,
+    pub shop_views_anonymized: u64,
+    pub retained: Vec<&'static str>,
+}
@@ -43,6 +43,10 @@ pub struct AdminSession {
 // In-memory session store (for production, use Redis or database)
 pub type SessionStore = Arc<RwLock<HashMap<String, AdminSession>>>;
 
+// Wallet sign-in sessions for profile viewers, kept apart from admin sessions
+#[derive(Clone, Default)]
+pub struct ProfileSessionStore(pub SessionStore);
+
 // In-memory nonce store (expires after 5 minutes)
 #[derive(Debug, Clone)]
 pub struct NonceData {
@@ -0,0 +1,60 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct RecordItemViewRequest {
+    pub viewer_address: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShopAnalyticsQuery {
+    pub from: Option<DateTime<Utc>>, // Defaults to 30 days before `to`
+    pub to: Option<DateTime<Utc>>,   // Defaults to now
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShopAnalyticsResponse {
+    pub shop_id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Paid and fulfilled orders, by payment time
+    pub order_count: i64,
+    pub revenue_by_token: Vec<TokenRevenue>,
+    /// Locked quote for USD-priced orders, current token price for the rest
+    pub revenue_usd: f64,
+    pub orders_per_day: Vec<DailyOrders>,
+    pub top_items: Vec<TopItem>,
+    pub unique_buyers: i64,
+    pub repeat_buyers: i64,
+    pub repeat_buyer_rate: f64,
+    pub views: i64,
+    pub unique_viewers: i64,
+    /// order_count / views
+    pub conversion_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenRevenue {
+    pub token_address: Option<String>,
+    pub token_symbol: String,
+    pub order_count: i64,
+    pub amount: String, // Smallest token unit
+    pub revenue_usd: Option<f64>, // None when the token has no known USD price
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyOrders {
+    pub date: NaiveDate,
+    pub orders: i64,
+    pub revenue_usd: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopItem {
+    pub item_id: String,
+    pub name: String,
+    pub units_sold: i64,
+    pub order_count: i64,
+    pub views: i64,
+    pub conversion_rate: f64,
+}
@@ -46,6 +46,14 @@ pub struct FeedSnapshot {
     pub fetched_at: DateTime<Utc>,
 }
 
+/// Latest USD price of a token accepted at shop checkout
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TokenPrice {
+    pub coingecko_id: String,
+    pub price_usd: f64,
+    pub fetched_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct FeedEvent {
     pub id: Uuid,
@@ -3,6 +3,8 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::models::SearchHighlight;
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct PublicGroup {
     pub id: Uuid,
@@ -49,6 +51,9 @@ pub struct PublicGroupResponse {
     pub is_public: bool,
     pub member_count: i32,
     pub created_at: String,
+    /// Which attribute matched, on search results only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<SearchHighlight>,
 }
 
 impl From<PublicGroup> for PublicGroupResponse {
@@ -64,6 +69,7 @@ impl From<PublicGroup> for PublicGroupResponse {
             is_public: g.is_public,
             member_count: g.member_count,
             created_at: g.created_at.to_rfc3339(),
+            highlight: None,
         }
     }
 }
@@ -0,0 +1,57 @@
+use crate::models::ItemResponse;
+use serde::{Deserialize, Serialize};
+
+/// Filters for the cross-shop item search. Only shops whose group is listed
+/// in the public group directory are searched.
+#[derive(Debug, Deserialize)]
+pub struct MarketplaceSearchQuery {
+    pub q: Option<String>, // Full-text over item name and description
+    pub token: Option<String>, // Payment token symbol, e.g. ETH
+    pub category: Option<String>,
+    pub tag: Option<String>,
+    pub min_price_usd: Option<f64>,
+    pub max_price_usd: Option<f64>,
+    pub min_rating: Option<f64>,
+    pub sort: Option<String>, // relevance | newest | price_asc | price_desc | popularity
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarketplaceSearchResponse {
+    pub items: Vec<MarketplaceItemResponse>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarketplaceItemResponse {
+    #[serde(flatten)]
+    pub item: ItemResponse,
+    /// USD price, or the token price at the latest rate; None when unknown
+    pub price_usd_estimate: Option<f64>,
+    /// Units in paid and fulfilled orders
+    pub units_sold: i64,
+    pub shop: MarketplaceShop,
+    pub group: MarketplaceGroup,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarketplaceShop {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarketplaceGroup {
+    pub conversation_id: String,
+    pub name: String,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MarketplaceCategory {
+    pub category: String,
+    pub item_count: i64,
+}
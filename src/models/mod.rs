@@ -6,6 +6,14 @@ pub mod profile;
 pub mod group;
 pub mod alpha_bot;
 pub mod feed;
+pub mod order;
+pub mod promotion;
+pub mod analytics;
+pub mod review;
+pub mod marketplace;
+pub mod social;
+pub mod upload;
+pub mod account;
 
 pub use payment::*;
 pub use token_gate::*;
@@ -13,3 +21,11 @@ pub use shop::*;
 pub use admin::*;
 pub use profile::*;
 pub use group::*;
+pub use order::*;
+pub use promotion::*;
+pub use analytics::*;
+pub use review::*;
+pub use marketplace::*;
+pub use social::*;
+pub use upload::*;
+pub use account::*;
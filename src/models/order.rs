@@ -0,0 +1,205 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Order {
+    pub id: Uuid,
+    pub shop_id: Option<Uuid>, // None once the shop has been deleted
+    pub conversation_id: String,
+    pub buyer_address: String,
+    pub seller_address: String,
+    pub token_address: Option<String>, // None for native ETH
+    pub token_symbol: String,
+    pub total_amount: String, // Smallest token unit, like transactions.amount
+    pub status: OrderStatus,
+    pub tx_hash: Option<String>,
+    pub refund_tx_hash: Option<String>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub fulfilled_at: Option<DateTime<Utc>>,
+    pub refunded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub reserved_until: Option<DateTime<Utc>>, // Pending orders expire after this
+    pub quote_usd_cents: Option<i64>, // Set when the order was priced in USD
+    pub quote_token_price_usd: Option<f64>,
+    pub quote_expires_at: Option<DateTime<Utc>>,
+    pub min_accepted_amount: Option<String>, // total_amount less the slippage tolerance
+    pub promotion_id: Option<Uuid>,
+    pub promo_code: Option<String>,
+    pub discount_amount: Option<String>, // Promo discount already taken off total_amount
+    pub discount_usd_cents: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "order_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    Pending,
+    Paid,
+    Fulfilled,
+    Refunded,
+    Expired,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct OrderItem {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub item_id: Option<Uuid>,
+    pub name: String,
+    pub price: String,
+    pub quantity: i32,
+    pub created_at: DateTime<Utc>,
+    pub reserved: bool, // Units were taken out of the item's stock
+    pub price_usd_cents: Option<i64>,
+    pub rule_id: Option<Uuid>, // Holder discount tier or purchase gate that applied
+    pub rule_name: Option<String>,
+    pub discount_bps: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOrderRequest {
+    pub buyer_address: String,
+    pub items: Vec<OrderItemRequest>,
+    pub pay_with: Option<String>, // Token symbol for USD-priced items, e.g. "ETH" or "USDC"
+    pub promo_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrderItemRequest {
+    pub item_id: Uuid,
+    #[serde(default = "default_quantity")]
+    pub quantity: i32,
+}
+
+fn default_quantity() -> i32 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefundOrderRequest {
+    pub refund_tx_hash: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderResponse {
+    pub id: String,
+    pub shop_id: Option<String>,
+    pub conversation_id: String,
+    pub buyer_address: String,
+    pub seller_address: String,
+    pub token_address: Option<String>,
+    pub token_symbol: String,
+    pub total_amount: String,
+    pub total_usd: Option<f64>,
+    pub quote_token_price_usd: Option<f64>,
+    pub quote_expires_at: Option<DateTime<Utc>>,
+    pub min_accepted_amount: Option<String>,
+    pub promo_code: Option<String>,
+    pub discount_amount: Option<String>,
+    pub discount_usd: Option<f64>,
+    pub status: OrderStatus,
+    pub tx_hash: Option<String>,
+    pub refund_tx_hash: Option<String>,
+    pub items: Vec<OrderItemResponse>,
+    pub created_at: DateTime<Utc>,
+    pub reserved_until: Option<DateTime<Utc>>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub fulfilled_at: Option<DateTime<Utc>>,
+    pub refunded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderItemResponse {
+    pub item_id: Option<String>,
+    pub name: String,
+    pub price: String,
+    pub price_usd: Option<f64>,
+    pub quantity: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_name: Option<String>,
+    pub discount_bps: i32,
+}
+
+impl From<Order> for OrderResponse {
+    fn from(order: Order) -> Self {
+        OrderResponse {
+            id: order.id.to_string(),
+            shop_id: order.shop_id.map(|id| id.to_string()),
+            conversation_id: order.conversation_id,
+            buyer_address: order.buyer_address,
+            seller_address: order.seller_address,
+            token_address: order.token_address,
+            token_symbol: order.token_symbol,
+            total_amount: order.total_amount,
+            total_usd: order.quote_usd_cents.map(|c| c as f64 / 100.0),
+            quote_token_price_usd: order.quote_token_price_usd,
+            quote_expires_at: order.quote_expires_at,
+            min_accepted_amount: order.min_accepted_amount,
+            promo_code: order.promo_code,
+            discount_amount: order.discount_amount,
+            discount_usd: order.discount_usd_cents.map(|c| c as f64 / 100.0),
+            status: order.status,
+            tx_hash: order.tx_hash,
+            refund_tx_hash: order.refund_tx_hash,
+            items: vec![],
+            created_at: order.created_at,
+            reserved_until: order.reserved_until,
+            paid_at: order.paid_at,
+            fulfilled_at: order.fulfilled_at,
+            refunded_at: order.refunded_at,
+        }
+    }
+}
+
+impl From<OrderItem> for OrderItemResponse {
+    fn from(item: OrderItem) -> Self {
+        OrderItemResponse {
+            item_id: item.item_id.map(|id| id.to_string()),
+            name: item.name,
+            price: item.price,
+            price_usd: item.price_usd_cents.map(|c| c as f64 / 100.0),
+            quantity: item.quantity,
+            rule_name: item.rule_name,
+            discount_bps: item.discount_bps,
+        }
+    }
+}
+
+// ── Digital delivery ──
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryNonceRequest {
+    pub wallet_address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryNonceResponse {
+    pub nonce: String,
+    pub message: String,
+}
+
+/// Buyer proves wallet ownership by signing the message from the nonce endpoint
+#[derive(Debug, Deserialize)]
+pub struct DeliveryRequest {
+    pub wallet_address: String,
+    pub nonce: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveredItem {
+    pub item_id: Option<String>,
+    pub name: String,
+    pub kind: Option<String>,
+    pub content: Option<String>,
+    pub license_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryResponse {
+    pub order_id: String,
+    pub items: Vec<DeliveredItem>,
+}
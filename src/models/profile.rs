@@ -17,6 +17,21 @@ pub struct UserProfile {
     pub basename_discoverable: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub basename_verified_at: Option<DateTime<Utc>>, // Last time the name resolved to this wallet
+    pub basename_checked_at: Option<DateTime<Utc>>,
+    pub ens_name: Option<String>,
+    pub ens_avatar: Option<String>,
+    pub ens_url: Option<String>,
+    pub ens_twitter: Option<String>,
+    pub ens_github: Option<String>,
+    pub ens_discoverable: bool,
+    pub ens_resolved_at: Option<DateTime<Utc>>, // Cache timestamp, see ens_service::cache_ttl
+    pub discoverable_by_username: bool,
+    pub discoverable_by_wallet: bool,
+    pub discoverable_by_display_name: bool,
+    pub profile_visibility: String, // everyone | contacts | nobody
+    pub hide_bio_from_non_contacts: bool,
+    pub hide_avatar_from_non_contacts: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,8 +53,9 @@ pub struct UpdateProfileRequest {
     pub display_name: Option<String>,
     pub avatar_url: Option<String>,
     pub bio: Option<String>,
-    pub basename: Option<String>,
+    pub basename: Option<String>, // Must resolve to wallet_address on Base; "" clears it
     pub basename_discoverable: Option<bool>,
+    pub ens_discoverable: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -52,11 +68,36 @@ pub struct ProfileResponse {
     pub bio: Option<String>,
     pub basename: Option<String>,
     pub basename_discoverable: bool,
+    pub basename_verified_at: Option<DateTime<Utc>>,
+    pub ens: Option<EnsResponse>,
+    pub ens_discoverable: bool,
     pub created_at: DateTime<Utc>,
+    /// Set when the profile was looked up by a name it has since been renamed from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renamed_to: Option<String>,
+}
+
+/// Mainnet ENS identity, present once the wallet has a verified primary name
+#[derive(Debug, Serialize)]
+pub struct EnsResponse {
+    pub name: String,
+    pub avatar: Option<String>,
+    pub url: Option<String>,
+    pub twitter: Option<String>,
+    pub github: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
 }
 
 impl From<UserProfile> for ProfileResponse {
     fn from(profile: UserProfile) -> Self {
+        let ens = profile.ens_name.map(|name| EnsResponse {
+            name,
+            avatar: profile.ens_avatar,
+            url: profile.ens_url,
+            twitter: profile.ens_twitter,
+            github: profile.ens_github,
+            resolved_at: profile.ens_resolved_at,
+        });
         Self {
             wallet_address: profile.wallet_address,
             inbox_id: profile.inbox_id,
@@ -66,11 +107,85 @@ impl From<UserProfile> for ProfileResponse {
             bio: profile.bio,
             basename: profile.basename,
             basename_discoverable: profile.basename_discoverable,
+            basename_verified_at: profile.basename_verified_at,
+            ens,
+            ens_discoverable: profile.ens_discoverable,
             created_at: profile.created_at,
+            renamed_to: None,
         }
     }
 }
 
+/// Up to MAX_BATCH_SIZE inbox ids and wallet addresses combined
+#[derive(Debug, Deserialize)]
+pub struct BatchProfilesRequest {
+    #[serde(default)]
+    pub inbox_ids: Vec<String>,
+    #[serde(default)]
+    pub wallet_addresses: Vec<String>,
+}
+
+/// Found profiles keyed by the requested inbox id or lowercased wallet address.
+/// Ids that are missing (or hidden from the viewer) are simply absent.
+#[derive(Debug, Serialize)]
+pub struct BatchProfilesResponse {
+    pub profiles: std::collections::BTreeMap<String, ProfileResponse>,
+}
+
+/// Privacy settings, only shown to the profile owner
+#[derive(Debug, Serialize, FromRow)]
+pub struct PrivacySettings {
+    pub discoverable_by_username: bool,
+    pub discoverable_by_wallet: bool,
+    pub discoverable_by_display_name: bool,
+    pub profile_visibility: String,
+    pub hide_bio_from_non_contacts: bool,
+    pub hide_avatar_from_non_contacts: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePrivacyRequest {
+    pub discoverable_by_username: Option<bool>,
+    pub discoverable_by_wallet: Option<bool>,
+    pub discoverable_by_display_name: Option<bool>,
+    pub profile_visibility: Option<String>,
+    pub hide_bio_from_non_contacts: Option<bool>,
+    pub hide_avatar_from_non_contacts: Option<bool>,
+}
+
+/// An admin-managed username: 'reserved' for a brand (optionally assigned to a
+/// wallet) or 'blocked' for everyone
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ReservedUsername {
+    pub id: Uuid,
+    pub name: String,
+    pub skeleton: String, // Confusable-folded form, see username_skeleton()
+    pub kind: String,
+    pub reason: Option<String>,
+    pub assigned_wallet: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReserveUsernameRequest {
+    pub name: String,
+    #[serde(default = "default_reservation_kind")]
+    pub kind: String, // reserved | blocked
+    pub reason: Option<String>,
+    pub wallet_address: Option<String>, // Wallet allowed to claim a reserved name
+}
+
+fn default_reservation_kind() -> String {
+    "reserved".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignUsernameRequest {
+    pub wallet_address: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SearchResult {
     pub wallet_address: String,
@@ -79,6 +194,22 @@ pub struct SearchResult {
     pub display_name: Option<String>,
     pub avatar_url: Option<String>,
     pub basename: Option<String>,
+    pub ens_name: Option<String>,
+    /// Which attribute matched, on search results only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<SearchHighlight>,
+}
+
+/// The best matching attribute of a search result
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SearchHighlight {
+    #[sqlx(rename = "match_field")]
+    pub field: String,
+    #[sqlx(rename = "match_value")]
+    pub value: String,
+    #[serde(rename = "match")]
+    #[sqlx(rename = "match_kind")]
+    pub kind: String, // exact | prefix | contains | fuzzy
 }
 
 impl From<UserProfile> for SearchResult {
@@ -90,6 +221,8 @@ impl From<UserProfile> for SearchResult {
             display_name: profile.display_name,
             avatar_url: profile.avatar_url,
             basename: profile.basename,
+            ens_name: profile.ens_name,
+            highlight: None,
         }
     }
 }
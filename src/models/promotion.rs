@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ShopPromotion {
+    pub id: Uuid,
+    pub shop_id: Uuid,
+    pub code: String,
+    pub discount_type: String, // "percentage" or "fixed"
+    pub percent_off_bps: Option<i32>,
+    pub amount_off: Option<String>, // Smallest unit of amount_off_token_address
+    pub amount_off_token_address: Option<String>, // None for native ETH
+    pub amount_off_usd_cents: Option<i64>, // Fixed discount for USD-priced orders
+    pub item_ids: Option<Vec<Uuid>>, // None when the code applies to the whole shop
+    pub max_redemptions: Option<i32>,
+    pub max_per_wallet: Option<i32>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body for creating a promotion and for replacing one on update
+#[derive(Debug, Deserialize)]
+pub struct PromotionRequest {
+    pub code: String,
+    pub discount_type: String,
+    pub percent_off_bps: Option<i32>,
+    pub amount_off: Option<String>,
+    pub amount_off_token_address: Option<String>,
+    pub amount_off_usd: Option<f64>,
+    pub item_ids: Option<Vec<Uuid>>,
+    pub max_redemptions: Option<i32>,
+    pub max_per_wallet: Option<i32>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default = "default_active")]
+    pub is_active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct PromotionResponse {
+    pub id: String,
+    pub shop_id: String,
+    pub code: String,
+    pub discount_type: String,
+    pub percent_off_bps: Option<i32>,
+    pub amount_off: Option<String>,
+    pub amount_off_token_address: Option<String>,
+    pub amount_off_usd: Option<f64>,
+    pub item_ids: Option<Vec<String>>,
+    pub max_redemptions: Option<i32>,
+    pub max_per_wallet: Option<i32>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    /// Redemptions on pending, paid and fulfilled orders
+    pub redemption_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PromotionRedemption {
+    pub order_id: Uuid,
+    pub buyer_address: String,
+    pub discount_amount: String,
+    pub discount_usd_cents: Option<i64>,
+    pub order_status: crate::models::OrderStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ShopPromotion> for PromotionResponse {
+    fn from(promotion: ShopPromotion) -> Self {
+        PromotionResponse {
+            id: promotion.id.to_string(),
+            shop_id: promotion.shop_id.to_string(),
+            code: promotion.code,
+            discount_type: promotion.discount_type,
+            percent_off_bps: promotion.percent_off_bps,
+            amount_off: promotion.amount_off,
+            amount_off_token_address: promotion.amount_off_token_address,
+            amount_off_usd: promotion.amount_off_usd_cents.map(|c| c as f64 / 100.0),
+            item_ids: promotion
+                .item_ids
+                .map(|ids| ids.iter().map(|id| id.to_string()).collect()),
+            max_redemptions: promotion.max_redemptions,
+            max_per_wallet: promotion.max_per_wallet,
+            starts_at: promotion.starts_at,
+            expires_at: promotion.expires_at,
+            is_active: promotion.is_active,
+            redemption_count: 0,
+            created_at: promotion.created_at,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ShopItemReview {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub shop_id: Uuid,
+    pub order_id: Uuid,
+    pub buyer_address: String,
+    pub rating: i16,
+    pub body: Option<String>,
+    pub seller_reply: Option<String>,
+    pub seller_replied_at: Option<DateTime<Utc>>,
+    pub is_hidden: bool,
+    pub hidden_reason: Option<String>,
+    pub hidden_by: Option<String>,
+    pub hidden_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewNonceRequest {
+    pub wallet_address: String,
+    pub order_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewNonceResponse {
+    pub nonce: String,
+    pub message: String,
+}
+
+/// Buyer proves wallet ownership by signing the message from the nonce endpoint
+#[derive(Debug, Deserialize)]
+pub struct CreateReviewRequest {
+    pub order_id: Uuid,
+    pub rating: i16,
+    pub body: Option<String>,
+    pub wallet_address: String,
+    pub nonce: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewReplyRequest {
+    pub reply: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HideReviewRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewResponse {
+    pub id: String,
+    pub item_id: String,
+    pub order_id: String,
+    pub buyer_address: String,
+    pub rating: i16,
+    pub body: Option<String>,
+    pub seller_reply: Option<String>,
+    pub seller_replied_at: Option<DateTime<Utc>>,
+    pub verified_purchase: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Review as admins see it, including moderation state
+#[derive(Debug, Serialize)]
+pub struct AdminReviewResponse {
+    #[serde(flatten)]
+    pub review: ReviewResponse,
+    pub shop_id: String,
+    pub is_hidden: bool,
+    pub hidden_reason: Option<String>,
+    pub hidden_by: Option<String>,
+    pub hidden_at: Option<DateTime<Utc>>,
+}
+
+impl From<ShopItemReview> for ReviewResponse {
+    fn from(review: ShopItemReview) -> Self {
+        ReviewResponse {
+            id: review.id.to_string(),
+            item_id: review.item_id.to_string(),
+            order_id: review.order_id.to_string(),
+            buyer_address: review.buyer_address,
+            rating: review.rating,
+            body: review.body,
+            seller_reply: review.seller_reply,
+            seller_replied_at: review.seller_replied_at,
+            verified_purchase: true, // Reviews can only be written against a paid order
+            created_at: review.created_at,
+        }
+    }
+}
+
+impl From<ShopItemReview> for AdminReviewResponse {
+    fn from(review: ShopItemReview) -> Self {
+        AdminReviewResponse {
+            shop_id: review.shop_id.to_string(),
+            is_hidden: review.is_hidden,
+            hidden_reason: review.hidden_reason.clone(),
+            hidden_by: review.hidden_by.clone(),
+            hidden_at: review.hidden_at,
+            review: ReviewResponse::from(review),
+        }
+    }
+}
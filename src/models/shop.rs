@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use crate::models::{RequirementStatus, TokenRequirement};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -24,6 +25,16 @@ pub struct ShopItem {
     pub image_url: Option<String>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    pub stock: Option<i32>, // Units still available, None for unlimited
+    pub max_per_wallet: Option<i32>,
+    pub sale_starts_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub sale_ends_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub deliverable_kind: Option<String>,
+    #[serde(skip_serializing)]
+    pub deliverable_ciphertext: Option<String>, // Only decrypted for paid buyers
+    pub price_usd_cents: Option<i64>, // Fiat price, converted to the payment token at checkout
+    pub category: Option<String>,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -41,20 +52,135 @@ pub struct UpdateShopRequest {
 pub struct CreateItemRequest {
     pub name: String,
     pub description: Option<String>,
-    pub price: String,
+    #[serde(default)]
+    pub price: String, // Ignored at checkout when price_usd is set
+    pub price_usd: Option<f64>,
     pub token_address: Option<String>,
     pub token_symbol: String,
     pub image_url: Option<String>,
+    pub stock: Option<i32>,
+    pub max_per_wallet: Option<i32>,
+    pub sale_starts_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub sale_ends_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateItemRequest {
     pub name: String,
     pub description: Option<String>,
-    pub price: String,
+    #[serde(default)]
+    pub price: String, // Ignored at checkout when price_usd is set
+    pub price_usd: Option<f64>,
     pub token_address: Option<String>,
     pub token_symbol: String,
     pub image_url: Option<String>,
+    pub stock: Option<i32>,
+    pub max_per_wallet: Option<i32>,
+    pub sale_starts_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub sale_ends_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetDeliverableRequest {
+    pub kind: String, // "text", "link" or "file"
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddLicenseCodesRequest {
+    pub codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LicenseCodesResponse {
+    pub item_id: String,
+    pub added: usize,
+    pub available: i64,
+}
+
+// ── Holder rules ──
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ShopItemRule {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub rule_type: String, // "gate" or "discount"
+    pub name: String,
+    pub operator: String,
+    pub requirements: serde_json::Value, // Vec<TokenRequirement>
+    pub discount_bps: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ItemRuleRequest {
+    pub name: String,
+    #[serde(default = "default_rule_operator")]
+    pub operator: String, // "AND" or "OR"
+    pub requirements: Vec<TokenRequirement>,
+    /// Discount tiers only: basis points off the price (2000 = 20%)
+    #[serde(default)]
+    pub discount_bps: i32,
+}
+
+fn default_rule_operator() -> String {
+    "AND".to_string()
+}
+
+/// Replaces all rules on the item
+#[derive(Debug, Deserialize)]
+pub struct SetItemRulesRequest {
+    pub gate: Option<ItemRuleRequest>,
+    #[serde(default)]
+    pub discounts: Vec<ItemRuleRequest>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ItemRuleResponse {
+    pub id: String,
+    pub name: String,
+    pub operator: String,
+    pub requirements: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount_bps: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ItemRulesResponse {
+    pub item_id: String,
+    pub gate: Option<ItemRuleResponse>,
+    pub discounts: Vec<ItemRuleResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ItemEligibilityResponse {
+    pub item_id: String,
+    pub wallet_address: String,
+    pub allowed: bool,
+    /// Rule that applies at checkout: the best discount tier met, else the gate
+    pub rule_id: Option<String>,
+    pub rule_name: Option<String>,
+    pub discount_bps: i32,
+    pub gate_requirements: Vec<RequirementStatus>,
+}
+
+impl From<ShopItemRule> for ItemRuleResponse {
+    fn from(rule: ShopItemRule) -> Self {
+        ItemRuleResponse {
+            id: rule.id.to_string(),
+            discount_bps: (rule.rule_type == "discount").then_some(rule.discount_bps),
+            name: rule.name,
+            operator: rule.operator,
+            requirements: rule.requirements,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -64,6 +190,8 @@ pub struct ShopResponse {
     pub name: String,
     pub owner_address: String,
     pub items: Vec<ItemResponse>,
+    pub average_rating: Option<f64>, // Across all items, None until the first review
+    pub review_count: i64,
     pub created_at: String,
 }
 
@@ -74,9 +202,20 @@ pub struct ItemResponse {
     pub name: String,
     pub description: Option<String>,
     pub price: String,
+    pub price_usd: Option<f64>,
     pub token_address: Option<String>,
     pub token_symbol: String,
     pub image_url: Option<String>,
+    pub stock: Option<i32>,
+    pub sold_out: bool,
+    pub max_per_wallet: Option<i32>,
+    pub sale_starts_at: Option<String>,
+    pub sale_ends_at: Option<String>,
+    pub deliverable_kind: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub average_rating: Option<f64>,
+    pub review_count: i64,
     pub created_at: String,
 }
 
@@ -88,6 +227,8 @@ impl From<Shop> for ShopResponse {
             name: shop.name,
             owner_address: shop.owner_address,
             items: vec![],
+            average_rating: None,
+            review_count: 0,
             created_at: shop.created_at.to_string(),
         }
     }
@@ -101,9 +242,20 @@ impl From<ShopItem> for ItemResponse {
             name: item.name,
             description: item.description,
             price: item.price,
+            price_usd: item.price_usd_cents.map(|c| c as f64 / 100.0),
             token_address: item.token_address,
             token_symbol: item.token_symbol,
             image_url: item.image_url,
+            stock: item.stock,
+            sold_out: item.stock == Some(0),
+            max_per_wallet: item.max_per_wallet,
+            sale_starts_at: item.sale_starts_at.map(|t| t.to_rfc3339()),
+            sale_ends_at: item.sale_ends_at.map(|t| t.to_rfc3339()),
+            deliverable_kind: item.deliverable_kind,
+            category: item.category,
+            tags: item.tags,
+            average_rating: None,
+            review_count: 0,
             created_at: item.created_at.to_string(),
         }
     }
@@ -0,0 +1,45 @@
+use crate::models::SearchResult;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct SocialListQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SocialListResponse {
+    pub profiles: Vec<SocialListEntry>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SocialListEntry {
+    #[serde(flatten)]
+    pub profile: SearchResult,
+    pub since: DateTime<Utc>, // When the follow / block was made
+}
+
+/// How `wallet_address` relates to `other_wallet`
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct RelationshipResponse {
+    pub wallet_address: String,
+    pub other_wallet: String,
+    pub following: bool,
+    pub followed_by: bool,
+    pub contacts: bool, // Mutual follow
+    pub blocking: bool,
+    pub blocked_by: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FollowSuggestion {
+    #[serde(flatten)]
+    pub profile: SearchResult,
+    /// People you follow who follow them
+    pub mutual_count: i64,
+    pub follows_you: bool,
+}
@@ -11,15 +11,38 @@ pub struct TokenGate {
     pub token_symbol: String,
     pub min_amount: String,
     pub operator: String,
+    pub min_hold_days: Option<i32>,
+    pub requirement_type: String,
+    pub min_profile_age_days: Option<i32>,
+    pub eas_schema_uid: Option<String>,
+    pub eas_attester: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenRequirement {
+    /// "token" (default), "basename", "profile_age", "username" or "eas"
+    #[serde(default = "default_requirement_type")]
+    pub requirement_type: String,
     pub token_address: Option<String>,
+    /// Required for token requirements; other types get a default label
+    #[serde(default)]
     pub token_symbol: String,
+    #[serde(default)]
     pub min_amount: String,
+    /// Balance must have been held continuously for this many days (1-365)
+    pub min_hold_days: Option<i32>,
+    /// Profile must be at least this many days old (profile_age requirements)
+    pub min_profile_age_days: Option<i32>,
+    /// EAS schema UID the wallet needs a valid attestation for (eas requirements)
+    pub eas_schema_uid: Option<String>,
+    /// Only accept attestations from this attester (eas requirements, optional)
+    pub eas_attester: Option<String>,
+}
+
+fn default_requirement_type() -> String {
+    "token".to_string()
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,17 +59,31 @@ pub struct TokenGateResponse {
 
 #[derive(Debug, Serialize)]
 pub struct TokenRequirementResponse {
+    pub requirement_type: String,
     pub token_address: Option<String>,
     pub token_symbol: String,
     pub min_amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_hold_days: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_profile_age_days: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eas_schema_uid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eas_attester: Option<String>,
 }
 
 impl From<TokenGate> for TokenRequirementResponse {
     fn from(gate: TokenGate) -> Self {
         Self {
+            requirement_type: gate.requirement_type,
             token_address: gate.token_address,
             token_symbol: gate.token_symbol,
             min_amount: gate.min_amount,
+            min_hold_days: gate.min_hold_days,
+            min_profile_age_days: gate.min_profile_age_days,
+            eas_schema_uid: gate.eas_schema_uid,
+            eas_attester: gate.eas_attester,
         }
     }
 }
@@ -55,18 +92,221 @@ impl From<TokenGate> for TokenRequirementResponse {
 pub struct VerifyTokenGateRequest {
     pub conversation_id: String,
     pub wallet_address: String,
+    /// Ask for a signed gate pass when the check succeeds
+    #[serde(default)]
+    pub include_pass: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct VerifyTokenGateResponse {
     pub allowed: bool,
     pub requirements_met: Vec<RequirementStatus>,
+    /// Block the balances were read at (None when the conversation has no gates)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gate_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gate_pass: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gate_pass_expires_at: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct RequirementStatus {
+    pub requirement_type: String,
+    /// Token symbol, or the label of a non-token requirement
     pub token: String,
     pub required: String,
     pub balance: String,
     pub met: bool,
+    /// Only for requirements with min_hold_days
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_hold_days: Option<i32>,
+    /// Full days the balance has been held, counted back from the checked block
+    /// (stops at min_hold_days, so it is a lower bound once the requirement is met)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub held_days: Option<i64>,
+    /// Date the current holding is known to start from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub held_since: Option<String>,
+}
+
+// ── Simulation (dry run) ──
+
+#[derive(Debug, Deserialize)]
+pub struct SimulateTokenGateRequest {
+    /// Draft requirements to test; the saved gate is used when omitted
+    pub requirements: Option<Vec<TokenRequirement>>,
+    /// Operator for the draft requirements (defaults to "AND")
+    pub operator: Option<String>,
+    pub wallet_addresses: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimulatedWalletResult {
+    pub wallet_address: String,
+    pub allowed: bool,
+    pub requirements_met: Vec<RequirementStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimulationSummary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errored: usize,
+    /// passed / (total - errored), 0.0 when nothing could be evaluated
+    pub pass_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimulateTokenGateResponse {
+    /// "draft" or "saved"
+    pub source: String,
+    pub gate: TokenGateResponse,
+    pub gate_hash: String,
+    pub block_number: u64,
+    pub results: Vec<SimulatedWalletResult>,
+    pub summary: SimulationSummary,
+}
+
+// ── Gate passes ──
+
+/// Claims of a signed gate pass (RS256 JWT)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatePassClaims {
+    pub iss: String,
+    /// Wallet address that passed the gate
+    pub sub: String,
+    pub conversation_id: String,
+    pub gate_hash: String,
+    pub block_number: u64,
+    pub chain_id: u64,
+    pub iat: i64,
+    pub exp: i64,
+    pub jti: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyGatePassRequest {
+    pub gate_pass: String,
+    /// Optional: reject the pass if it was issued for another conversation
+    pub conversation_id: Option<String>,
+    /// Optional: reject the pass if it was issued for another wallet
+    pub wallet_address: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyGatePassResponse {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claims: Option<GatePassClaims>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GatePassJwk {
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+    pub n: String,
+    pub e: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GatePassPublicKeyResponse {
+    pub issuer: String,
+    pub alg: String,
+    pub kid: String,
+    pub public_key_pem: String,
+    pub jwk: GatePassJwk,
+}
+
+// ── Membership re-verification ──
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct GateMembershipStatus {
+    pub id: Uuid,
+    pub conversation_id: String,
+    pub wallet_address: String,
+    pub qualifies: bool,
+    pub requirements_met: serde_json::Value,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub failing_since: Option<DateTime<Utc>>,
+    pub notified_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct GateReverifySettings {
+    pub conversation_id: String,
+    pub grace_period_hours: i32,
+    pub webhook_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Full list of current members, replaces whatever was known before
+#[derive(Debug, Deserialize)]
+pub struct SyncGateMembersRequest {
+    pub wallet_addresses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateReverifySettingsRequest {
+    pub grace_period_hours: Option<i32>,
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReverifySettingsResponse {
+    pub conversation_id: String,
+    pub grace_period_hours: i32,
+    pub webhook_url: Option<String>,
+}
+
+impl From<GateReverifySettings> for ReverifySettingsResponse {
+    fn from(s: GateReverifySettings) -> Self {
+        Self {
+            conversation_id: s.conversation_id,
+            grace_period_hours: s.grace_period_hours,
+            webhook_url: s.webhook_url,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GateEvictionEntry {
+    pub wallet_address: String,
+    pub failing_since: Option<String>,
+    pub last_checked_at: Option<String>,
+    pub requirements_met: serde_json::Value,
+}
+
+impl From<GateMembershipStatus> for GateEvictionEntry {
+    fn from(m: GateMembershipStatus) -> Self {
+        Self {
+            wallet_address: m.wallet_address,
+            failing_since: m.failing_since.map(|t| t.to_rfc3339()),
+            last_checked_at: m.last_checked_at.map(|t| t.to_rfc3339()),
+            requirements_met: m.requirements_met,
+        }
+    }
+}
+
+/// "No longer qualifies" list — returned by GET /evictions and POSTed to the webhook
+#[derive(Debug, Serialize)]
+pub struct GateEvictionReport {
+    pub conversation_id: String,
+    pub grace_period_hours: i32,
+    pub members: Vec<GateEvictionEntry>,
+    pub generated_at: String,
 }
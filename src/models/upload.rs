@@ -0,0 +1,13 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A processed image upload. `url` is what goes into `avatar_url` or a group's `image_url`.
+#[derive(Debug, Serialize)]
+pub struct UploadedImage {
+    pub url: String,
+    /// Square thumbnails keyed by edge length in pixels
+    pub thumbnails: BTreeMap<String, String>,
+    pub width: u32,
+    pub height: u32,
+    pub content_type: String,
+}
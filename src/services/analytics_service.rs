@@ -0,0 +1,291 @@
+use crate::{
+    db::DbPool,
+    models::{
+        DailyOrders, RecordItemViewRequest, ShopAnalyticsQuery, ShopAnalyticsResponse, TokenRevenue, TopItem,
+    },
+    services::{feed_service, order_service},
+};
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+const DEFAULT_RANGE_DAYS: i64 = 30;
+const MAX_RANGE_DAYS: i64 = 366;
+const TOP_ITEMS_LIMIT: i64 = 10;
+
+/// Paid revenue in one token. Amounts are NUMERIC sums cast to text so they don't
+/// overflow; `unquoted_amount` is the part not covered by a locked USD quote.
+#[derive(sqlx::FromRow)]
+struct TokenRevenueRow {
+    token_address: Option<String>,
+    token_symbol: String,
+    order_count: i64,
+    amount: String,
+    quoted_usd_cents: Option<i64>,
+    unquoted_amount: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct DailyRevenueRow {
+    day: NaiveDate,
+    token_address: Option<String>,
+    order_count: i64,
+    quoted_usd_cents: Option<i64>,
+    unquoted_amount: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TopItemRow {
+    item_id: Uuid,
+    name: String,
+    units_sold: i64,
+    order_count: i64,
+    views: i64,
+}
+
+pub async fn record_item_view(pool: &DbPool, item_id: &Uuid, req: RecordItemViewRequest) -> Result<()> {
+    let viewer_address = req
+        .viewer_address
+        .filter(|a| a.parse::<ethers::types::Address>().is_ok())
+        .map(|a| a.to_lowercase());
+
+    sqlx::query(
+        r#"
+        INSERT INTO shop_item_views (shop_id, item_id, viewer_address)
+        SELECT shop_id, id, $2 FROM shop_items WHERE id = $1
+        RETURNING id
+        "#,
+    )
+    .bind(item_id)
+    .bind(viewer_address)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Sales and traffic for a shop over [from, to). Orders count once paid (paid or
+/// fulfilled; refunded orders are left out) and are bucketed by payment time.
+/// Analytics include revenue, so only the shop owner (`seller_address`, the
+/// signed-in wallet) may read them
+pub async fn get_shop_analytics(
+    pool: &DbPool,
+    shop_id: &Uuid,
+    seller_address: &str,
+    query: ShopAnalyticsQuery,
+) -> Result<ShopAnalyticsResponse> {
+    let (owner_address,): (String,) = sqlx::query_as("SELECT owner_address FROM shops WHERE id = $1")
+        .bind(shop_id)
+        .fetch_one(pool)
+        .await?;
+    if !owner_address.eq_ignore_ascii_case(seller_address) {
+        return Err(anyhow!("Only the shop owner can view analytics"));
+    }
+
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS));
+    if from >= to {
+        return Err(anyhow!("'from' must be before 'to'"));
+    }
+    if to - from > Duration::days(MAX_RANGE_DAYS) {
+        return Err(anyhow!("Analytics cover at most {} days at a time", MAX_RANGE_DAYS));
+    }
+
+    let usd_prices = token_usd_prices(pool).await?;
+
+    let token_rows = sqlx::query_as::<_, TokenRevenueRow>(
+        r#"
+        SELECT token_address, token_symbol,
+               COUNT(*) AS order_count,
+               SUM(total_amount::NUMERIC)::TEXT AS amount,
+               SUM(quote_usd_cents)::BIGINT AS quoted_usd_cents,
+               SUM(total_amount::NUMERIC) FILTER (WHERE quote_usd_cents IS NULL)::TEXT AS unquoted_amount
+        FROM orders
+        WHERE shop_id = $1 AND status IN ('paid', 'fulfilled') AND paid_at >= $2 AND paid_at < $3
+        GROUP BY token_address, token_symbol
+        ORDER BY order_count DESC
+        "#,
+    )
+    .bind(shop_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    let mut order_count = 0;
+    let mut revenue_usd = 0.0;
+    let mut revenue_by_token = Vec::with_capacity(token_rows.len());
+    for row in token_rows {
+        let usd = usd_value(&usd_prices, &row.token_address, row.quoted_usd_cents, row.unquoted_amount.as_deref());
+        order_count += row.order_count;
+        revenue_usd += usd.unwrap_or(0.0);
+        revenue_by_token.push(TokenRevenue {
+            token_address: row.token_address,
+            token_symbol: row.token_symbol,
+            order_count: row.order_count,
+            amount: row.amount,
+            revenue_usd: usd,
+        });
+    }
+
+    let daily_rows = sqlx::query_as::<_, DailyRevenueRow>(
+        r#"
+        SELECT (paid_at AT TIME ZONE 'UTC')::DATE AS day, token_address,
+               COUNT(*) AS order_count,
+               SUM(quote_usd_cents)::BIGINT AS quoted_usd_cents,
+               SUM(total_amount::NUMERIC) FILTER (WHERE quote_usd_cents IS NULL)::TEXT AS unquoted_amount
+        FROM orders
+        WHERE shop_id = $1 AND status IN ('paid', 'fulfilled') AND paid_at >= $2 AND paid_at < $3
+        GROUP BY day, token_address
+        "#,
+    )
+    .bind(shop_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    let mut days: BTreeMap<NaiveDate, DailyOrders> = BTreeMap::new();
+    for row in daily_rows {
+        let usd = usd_value(&usd_prices, &row.token_address, row.quoted_usd_cents, row.unquoted_amount.as_deref());
+        let day = days.entry(row.day).or_insert(DailyOrders { date: row.day, orders: 0, revenue_usd: 0.0 });
+        day.orders += row.order_count;
+        day.revenue_usd += usd.unwrap_or(0.0);
+    }
+
+    let top_items = sqlx::query_as::<_, TopItemRow>(
+        r#"
+        WITH sold AS (
+            SELECT oi.item_id, SUM(oi.quantity)::BIGINT AS units_sold, COUNT(DISTINCT oi.order_id) AS order_count
+            FROM order_items oi
+            JOIN orders o ON o.id = oi.order_id
+            WHERE o.shop_id = $1 AND o.status IN ('paid', 'fulfilled') AND o.paid_at >= $2 AND o.paid_at < $3
+              AND oi.item_id IS NOT NULL
+            GROUP BY oi.item_id
+        ), viewed AS (
+            SELECT item_id, COUNT(*) AS views
+            FROM shop_item_views
+            WHERE shop_id = $1 AND created_at >= $2 AND created_at < $3
+            GROUP BY item_id
+        )
+        SELECT si.id AS item_id, si.name, sold.units_sold, sold.order_count, COALESCE(viewed.views, 0) AS views
+        FROM sold
+        JOIN shop_items si ON si.id = sold.item_id
+        LEFT JOIN viewed ON viewed.item_id = sold.item_id
+        ORDER BY sold.units_sold DESC, sold.order_count DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(shop_id)
+    .bind(from)
+    .bind(to)
+    .bind(TOP_ITEMS_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    let (unique_buyers, repeat_buyers): (i64, i64) = sqlx::query_as(
+        r#"
+        WITH buyers AS (
+            SELECT LOWER(buyer_address) AS buyer, COUNT(*) AS orders
+            FROM orders
+            WHERE shop_id = $1 AND status IN ('paid', 'fulfilled') AND paid_at >= $2 AND paid_at < $3
+            GROUP BY buyer
+        )
+        SELECT COUNT(*), COUNT(*) FILTER (WHERE orders > 1) FROM buyers
+        "#,
+    )
+    .bind(shop_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await?;
+
+    let (views, unique_viewers): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*), COUNT(DISTINCT viewer_address)
+        FROM shop_item_views
+        WHERE shop_id = $1 AND created_at >= $2 AND created_at < $3
+        "#,
+    )
+    .bind(shop_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(ShopAnalyticsResponse {
+        shop_id: shop_id.to_string(),
+        from,
+        to,
+        order_count,
+        revenue_by_token,
+        revenue_usd: round_cents(revenue_usd),
+        orders_per_day: days
+            .into_values()
+            .map(|mut d| {
+                d.revenue_usd = round_cents(d.revenue_usd);
+                d
+            })
+            .collect(),
+        top_items: top_items
+            .into_iter()
+            .map(|item| TopItem {
+                item_id: item.item_id.to_string(),
+                name: item.name,
+                units_sold: item.units_sold,
+                order_count: item.order_count,
+                views: item.views,
+                conversion_rate: ratio(item.order_count, item.views),
+            })
+            .collect(),
+        unique_buyers,
+        repeat_buyers,
+        repeat_buyer_rate: ratio(repeat_buyers, unique_buyers),
+        views,
+        unique_viewers,
+        conversion_rate: ratio(order_count, views),
+    })
+}
+
+/// Current USD price and decimals of each checkout token, keyed by lowercase
+/// address ("" for native ETH)
+async fn token_usd_prices(pool: &DbPool) -> Result<HashMap<String, (f64, i32)>> {
+    let mut prices = HashMap::new();
+    for token in order_service::QUOTE_TOKENS {
+        if let Some(price) = feed_service::get_token_price(pool, token.coingecko_id).await? {
+            prices.insert(token.address.unwrap_or_default().to_string(), (price.price_usd, token.decimals));
+        }
+    }
+    Ok(prices)
+}
+
+/// USD value of a revenue bucket: locked quotes as-is, everything else at the
+/// token's current price. None when part of it is in a token without a price.
+fn usd_value(
+    prices: &HashMap<String, (f64, i32)>,
+    token_address: &Option<String>,
+    quoted_usd_cents: Option<i64>,
+    unquoted_amount: Option<&str>,
+) -> Option<f64> {
+    let quoted = quoted_usd_cents.unwrap_or(0) as f64 / 100.0;
+    let Some(amount) = unquoted_amount.and_then(|a| a.parse::<f64>().ok()) else {
+        return Some(quoted);
+    };
+    let key = token_address.as_deref().unwrap_or_default().to_lowercase();
+    prices
+        .get(&key)
+        .map(|(price, decimals)| quoted + amount / 10f64.powi(*decimals) * price)
+}
+
+fn ratio(part: i64, whole: i64) -> f64 {
+    if whole > 0 {
+        part as f64 / whole as f64
+    } else {
+        0.0
+    }
+}
+
+fn round_cents(usd: f64) -> f64 {
+    (usd * 100.0).round() / 100.0
+}
@@ -0,0 +1,67 @@
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::services::{profile_service, token_gate_service};
+
+const LOOP_SLEEP_SECS: u64 = 600;
+const RECHECK_INTERVAL_SECS: i64 = 86400; // re-check each basename daily
+const BATCH_SIZE: i64 = 100;              // profiles checked per pass
+
+/// Spawn the basename refresh background task. Call once from main.rs.
+pub fn spawn(pool: PgPool) {
+    tokio::spawn(async move {
+        log::info!("🏷️ Basename refresher starting...");
+        run_loop(pool).await;
+    });
+}
+
+async fn run_loop(pool: PgPool) {
+    loop {
+        refresh_due(&pool).await;
+        tokio::time::sleep(Duration::from_secs(LOOP_SLEEP_SECS)).await;
+    }
+}
+
+async fn refresh_due(pool: &PgPool) {
+    let profiles = match profile_service::get_profiles_due_for_basename_check(
+        pool,
+        RECHECK_INTERVAL_SECS,
+        BATCH_SIZE,
+    )
+    .await
+    {
+        Ok(profiles) => profiles,
+        Err(e) => {
+            log::error!("Basename refresher: failed to load profiles: {}", e);
+            return;
+        }
+    };
+    if profiles.is_empty() {
+        return;
+    }
+
+    // Stringify the error right away: the boxed error is not Send
+    let provider = match token_gate_service::base_provider().map_err(|e| e.to_string()) {
+        Ok(provider) => provider,
+        Err(e) => {
+            log::error!("Basename refresher: no Base provider: {}", e);
+            return;
+        }
+    };
+
+    log::info!("🏷️ Re-checking {} basename(s)", profiles.len());
+    for profile in &profiles {
+        match profile_service::refresh_basename(pool, &provider, profile).await {
+            Ok(updated) if updated.basename != profile.basename => log::info!(
+                "Basename for {} changed from {:?} to {:?}",
+                profile.wallet_address, profile.basename, updated.basename
+            ),
+            Ok(_) => {}
+            // Leave the name in place on RPC errors; it is retried next pass
+            Err(e) => log::warn!(
+                "Basename refresher: check failed for {}: {}",
+                profile.wallet_address, e
+            ),
+        }
+    }
+}
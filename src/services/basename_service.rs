@@ -0,0 +1,107 @@
+use ethers::prelude::*;
+use ethers::providers::namehash;
+use std::env;
+
+/// Basenames L2 resolver on Base mainnet
+const DEFAULT_L2_RESOLVER: &str = "0xC6d566A56A1aFf6508b41f6c90ff131615583BCD";
+const BASENAME_SUFFIX: &str = ".base.eth";
+/// ENSIP-19 reverse namespace for Base (coin type 0x80000000 | 8453)
+const BASE_REVERSE_SUFFIX: &str = "80002105.reverse";
+
+abigen!(
+    L2Resolver,
+    r#"[
+        function addr(bytes32 node) external view returns (address)
+        function name(bytes32 node) external view returns (string)
+    ]"#,
+);
+
+/// Full Basename for a stored value ("alice" and "alice.base.eth" both become "alice.base.eth")
+pub fn normalize_basename(basename: &str) -> String {
+    let name = basename.trim().to_lowercase();
+    if name.ends_with(BASENAME_SUFFIX) {
+        name
+    } else {
+        format!("{}{}", name, BASENAME_SUFFIX)
+    }
+}
+
+fn resolver_address() -> Result<Address, Box<dyn std::error::Error>> {
+    let address = env::var("BASENAME_RESOLVER_ADDRESS")
+        .unwrap_or_else(|_| DEFAULT_L2_RESOLVER.to_string());
+    Ok(address.parse()?)
+}
+
+/// Forward-resolve a Basename through the L2 resolver. Returns None when the name
+/// has no address record.
+pub async fn resolve_basename(
+    provider: &Provider<Http>,
+    basename: &str,
+    block: Option<BlockId>,
+) -> Result<Option<Address>, Box<dyn std::error::Error>> {
+    let resolver = L2Resolver::new(resolver_address()?, provider.clone().into());
+    let node = namehash(&normalize_basename(basename));
+
+    let mut call = resolver.addr(node.0);
+    if let Some(block) = block {
+        call = call.block(block);
+    }
+    let resolved = call.call().await?;
+
+    Ok(if resolved.is_zero() { None } else { Some(resolved) })
+}
+
+/// True when `basename` currently resolves to `wallet`
+pub async fn basename_owned_by(
+    provider: &Provider<Http>,
+    basename: &str,
+    wallet: Address,
+    block: Option<BlockId>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(resolve_basename(provider, basename, block).await? == Some(wallet))
+}
+
+/// Reverse record name for a wallet, e.g. "d8da…6045.80002105.reverse"
+fn reverse_name(wallet: Address) -> String {
+    format!("{}.{}", hex::encode(wallet.as_bytes()), BASE_REVERSE_SUFFIX)
+}
+
+/// The wallet's primary Basename: its reverse record, accepted only when the name
+/// forward-resolves back to the same wallet (anyone can set any reverse name).
+pub async fn primary_basename(
+    provider: &Provider<Http>,
+    wallet: Address,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let resolver = L2Resolver::new(resolver_address()?, provider.clone().into());
+    let name = resolver.name(namehash(&reverse_name(wallet)).0).call().await?;
+    let name = name.trim().to_lowercase();
+    if !name.ends_with(BASENAME_SUFFIX) {
+        return Ok(None);
+    }
+
+    Ok(if basename_owned_by(provider, &name, wallet, None).await? {
+        Some(name)
+    } else {
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_basename() {
+        assert_eq!(normalize_basename("Alice"), "alice.base.eth");
+        assert_eq!(normalize_basename("alice.base.eth"), "alice.base.eth");
+    }
+
+    #[test]
+    fn test_reverse_name() {
+        let wallet: Address = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".parse().unwrap();
+        assert_eq!(
+            reverse_name(wallet),
+            "d8da6bf26964af9d7eed9e03e53415d37aa96045.80002105.reverse"
+        );
+    }
+}
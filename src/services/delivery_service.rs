@@ -0,0 +1,355 @@
+use crate::{
+    db::DbPool,
+    models::{
+        AddLicenseCodesRequest, DeliveredItem, DeliveryRequest, DeliveryResponse, ItemResponse,
+        LicenseCodesResponse, NonceStore, Order, OrderItem, OrderStatus, SetDeliverableRequest, ShopItem,
+    },
+    services::admin_service,
+};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+const DELIVERABLE_KINDS: [&str; 3] = ["text", "link", "file"];
+const MAX_DELIVERABLE_LEN: usize = 8192;
+const MAX_CODES_PER_UPLOAD: usize = 1000;
+const MAX_CODE_LEN: usize = 256;
+
+// ── Encryption ──
+
+/// Server key for deliverables: 32 bytes, hex encoded in DELIVERABLE_ENCRYPTION_KEY
+fn encryption_key() -> Result<[u8; 32]> {
+    let hex_key = std::env::var("DELIVERABLE_ENCRYPTION_KEY")
+        .map_err(|_| anyhow!("Deliverable encryption is not configured"))?;
+    let bytes = hex::decode(hex_key.trim_start_matches("0x"))
+        .map_err(|_| anyhow!("DELIVERABLE_ENCRYPTION_KEY must be hex"))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("DELIVERABLE_ENCRYPTION_KEY must be 32 bytes"))
+}
+
+/// AES-256-GCM encrypt; returns base64(nonce || ciphertext || tag)
+fn seal(key: &[u8; 32], plaintext: &str) -> Result<String> {
+    let key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).map_err(|_| anyhow!("Invalid key"))?);
+
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce_bytes)
+        .map_err(|_| anyhow!("Failed to generate nonce"))?;
+
+    let mut in_out = plaintext.as_bytes().to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce_bytes), Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow!("Encryption failed"))?;
+
+    Ok(STANDARD.encode([nonce_bytes.as_slice(), &in_out].concat()))
+}
+
+fn open(key: &[u8; 32], sealed: &str) -> Result<String> {
+    let key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).map_err(|_| anyhow!("Invalid key"))?);
+
+    let data = STANDARD.decode(sealed)?;
+    if data.len() < NONCE_LEN {
+        return Err(anyhow!("Ciphertext too short"));
+    }
+    let (nonce_bytes, ciphertext) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce_bytes).map_err(|_| anyhow!("Invalid nonce"))?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow!("Decryption failed"))?;
+    Ok(String::from_utf8(plaintext.to_vec())?)
+}
+
+// ── Seller setup ──
+
+/// Load an item and check that `seller_address`, the signed-in wallet, owns its shop
+pub async fn load_seller_item(pool: &DbPool, item_id: &Uuid, seller_address: &str) -> Result<ShopItem> {
+    let item = sqlx::query_as::<_, ShopItem>("SELECT * FROM shop_items WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(pool)
+        .await?;
+
+    let (owner_address,): (String,) = sqlx::query_as("SELECT owner_address FROM shops WHERE id = $1")
+        .bind(item.shop_id)
+        .fetch_one(pool)
+        .await?;
+
+    if !owner_address.eq_ignore_ascii_case(seller_address) {
+        return Err(anyhow!("Only the shop owner can manage this item"));
+    }
+    Ok(item)
+}
+
+pub async fn set_deliverable(
+    pool: &DbPool,
+    item_id: &Uuid,
+    seller_address: &str,
+    req: SetDeliverableRequest,
+) -> Result<ItemResponse> {
+    if !DELIVERABLE_KINDS.contains(&req.kind.as_str()) {
+        return Err(anyhow!("Deliverable kind must be one of: text, link, file"));
+    }
+    if req.content.trim().is_empty() || req.content.len() > MAX_DELIVERABLE_LEN {
+        return Err(anyhow!("Deliverable content must be 1-{} bytes", MAX_DELIVERABLE_LEN));
+    }
+    load_seller_item(pool, item_id, seller_address).await?;
+
+    let ciphertext = seal(&encryption_key()?, &req.content)?;
+
+    let item = sqlx::query_as::<_, ShopItem>(
+        r#"
+        UPDATE shop_items
+        SET deliverable_kind = $1, deliverable_ciphertext = $2, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $3
+        RETURNING *
+        "#,
+    )
+    .bind(&req.kind)
+    .bind(&ciphertext)
+    .bind(item_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(ItemResponse::from(item))
+}
+
+pub async fn add_license_codes(
+    pool: &DbPool,
+    item_id: &Uuid,
+    seller_address: &str,
+    req: AddLicenseCodesRequest,
+) -> Result<LicenseCodesResponse> {
+    let mut seen = HashSet::new();
+    let codes: Vec<&str> = req
+        .codes
+        .iter()
+        .map(|c| c.trim())
+        .filter(|c| !c.is_empty() && seen.insert(*c))
+        .collect();
+
+    if codes.is_empty() {
+        return Err(anyhow!("No license codes provided"));
+    }
+    if codes.len() > MAX_CODES_PER_UPLOAD {
+        return Err(anyhow!("At most {} codes can be uploaded at once", MAX_CODES_PER_UPLOAD));
+    }
+    if codes.iter().any(|c| c.len() > MAX_CODE_LEN) {
+        return Err(anyhow!("License codes must be {} characters or less", MAX_CODE_LEN));
+    }
+    load_seller_item(pool, item_id, seller_address).await?;
+
+    let key = encryption_key()?;
+    let ciphertexts = codes
+        .iter()
+        .map(|c| seal(&key, c))
+        .collect::<Result<Vec<String>>>()?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO shop_item_license_codes (item_id, code_ciphertext)
+        SELECT $1, UNNEST($2::TEXT[])
+        "#,
+    )
+    .bind(item_id)
+    .bind(&ciphertexts)
+    .execute(pool)
+    .await?;
+
+    Ok(LicenseCodesResponse {
+        item_id: item_id.to_string(),
+        added: ciphertexts.len(),
+        available: available_codes(pool, item_id).await?,
+    })
+}
+
+pub async fn available_codes(pool: &DbPool, item_id: &Uuid) -> Result<i64> {
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM shop_item_license_codes WHERE item_id = $1 AND order_id IS NULL",
+    )
+    .bind(item_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(count)
+}
+
+// ── Buyer delivery ──
+
+/// Message the buyer signs to unlock an order's deliverables
+pub fn delivery_message(order_id: &Uuid, nonce: &str) -> String {
+    format!(
+        "Sign this message to unlock your BlocChat order.\n\nOrder: {}\nNonce: {}\n\nThis signature will not trigger any blockchain transaction or cost gas fees.",
+        order_id, nonce
+    )
+}
+
+/// Reveal an order's deliverables to its buyer. The request must carry a fresh
+/// signature from the buyer wallet, and the order must be paid. License codes are
+/// assigned on first delivery (one per unit) and the same codes are returned after.
+pub async fn deliver_order(
+    pool: &DbPool,
+    nonce_store: &NonceStore,
+    order_id: &Uuid,
+    req: DeliveryRequest,
+) -> Result<DeliveryResponse> {
+    admin_service::verify_nonce(nonce_store, &req.wallet_address, &req.nonce)?;
+    let message = delivery_message(order_id, &req.nonce);
+    if !admin_service::verify_signature(&req.wallet_address, &message, &req.signature)? {
+        return Err(anyhow!("Signature does not match wallet"));
+    }
+
+    let order = sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_one(pool)
+        .await?;
+
+    if !order.buyer_address.eq_ignore_ascii_case(&req.wallet_address) {
+        return Err(anyhow!("Only the buyer can unlock this order"));
+    }
+    if !matches!(order.status, OrderStatus::Paid | OrderStatus::Fulfilled) {
+        return Err(anyhow!("Order has not been paid"));
+    }
+
+    let order_items = sqlx::query_as::<_, OrderItem>(
+        "SELECT * FROM order_items WHERE order_id = $1 ORDER BY created_at ASC",
+    )
+    .bind(order_id)
+    .fetch_all(pool)
+    .await?;
+
+    let key = encryption_key()?;
+    let mut delivered = Vec::new();
+
+    for line in order_items {
+        let item = match line.item_id {
+            Some(item_id) => sqlx::query_as::<_, ShopItem>("SELECT * FROM shop_items WHERE id = $1")
+                .bind(item_id)
+                .fetch_optional(pool)
+                .await?,
+            None => None,
+        };
+
+        let content = match item.as_ref().and_then(|i| i.deliverable_ciphertext.as_deref()) {
+            Some(sealed) => Some(open(&key, sealed)?),
+            None => None,
+        };
+
+        let license_codes = match line.item_id {
+            Some(item_id) => assign_license_codes(pool, order_id, &item_id, line.quantity)
+                .await?
+                .iter()
+                .map(|sealed| open(&key, sealed))
+                .collect::<Result<Vec<String>>>()?,
+            None => vec![],
+        };
+
+        delivered.push(DeliveredItem {
+            item_id: line.item_id.map(|id| id.to_string()),
+            name: line.name,
+            kind: item.and_then(|i| i.deliverable_kind),
+            content,
+            license_codes,
+        });
+    }
+
+    // Digital goods count as fulfilled once the buyer has received them
+    sqlx::query(
+        "UPDATE orders SET status = 'fulfilled', fulfilled_at = NOW() WHERE id = $1 AND status = 'paid'",
+    )
+    .bind(order_id)
+    .execute(pool)
+    .await?;
+
+    Ok(DeliveryResponse {
+        order_id: order_id.to_string(),
+        items: delivered,
+    })
+}
+
+/// Top up the codes assigned to an order line to `quantity` and return all of them.
+/// SKIP LOCKED lets concurrent deliveries take different codes without waiting.
+async fn assign_license_codes(
+    pool: &DbPool,
+    order_id: &Uuid,
+    item_id: &Uuid,
+    quantity: i32,
+) -> Result<Vec<String>> {
+    let mut tx = pool.begin().await?;
+
+    let (assigned,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM shop_item_license_codes WHERE order_id = $1 AND item_id = $2",
+    )
+    .bind(order_id)
+    .bind(item_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let missing = quantity as i64 - assigned;
+    if missing > 0 {
+        let taken = sqlx::query(
+            r#"
+            UPDATE shop_item_license_codes SET order_id = $1, assigned_at = NOW()
+            WHERE id IN (
+                SELECT id FROM shop_item_license_codes
+                WHERE item_id = $2 AND order_id IS NULL
+                ORDER BY created_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            "#,
+        )
+        .bind(order_id)
+        .bind(item_id)
+        .bind(missing)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if (taken as i64) < missing {
+            // Items without a code pool never get codes; only a drained pool is worth a warning
+            let (has_pool,): (bool,) =
+                sqlx::query_as("SELECT EXISTS (SELECT 1 FROM shop_item_license_codes WHERE item_id = $1)")
+                    .bind(item_id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+            if has_pool {
+                log::warn!(
+                    "License code pool for item {} ran out: order {} is missing {} code(s)",
+                    item_id, order_id, missing - taken as i64
+                );
+            }
+        }
+    }
+
+    let codes: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT code_ciphertext FROM shop_item_license_codes
+        WHERE order_id = $1 AND item_id = $2
+        ORDER BY assigned_at, created_at
+        "#,
+    )
+    .bind(order_id)
+    .bind(item_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(codes.into_iter().map(|(c,)| c).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let key = [7u8; 32];
+        let sealed = seal(&key, "LICENSE-1234").unwrap();
+        assert_ne!(sealed, seal(&key, "LICENSE-1234").unwrap()); // fresh nonce each time
+        assert_eq!(open(&key, &sealed).unwrap(), "LICENSE-1234");
+        assert!(open(&[8u8; 32], &sealed).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use ethers::prelude::*;
+use std::env;
+use std::time::Duration;
+
+use crate::services::log_scanner;
+
+/// EAS predeploy on Base (same address on Base Sepolia)
+const DEFAULT_EAS_ADDRESS: &str = "0x4200000000000000000000000000000000000021";
+/// easscan indexer for Base, used to find candidate attestations
+const DEFAULT_EAS_GRAPHQL_URL: &str = "https://base.easscan.org/graphql";
+const GRAPHQL_TIMEOUT_SECS: u64 = 10;
+/// Newest candidates checked against the contract per wallet and schema
+const MAX_CANDIDATES: usize = 20;
+
+abigen!(
+    EAS,
+    r#"[
+        struct Attestation { bytes32 uid; bytes32 schema; uint64 time; uint64 expirationTime; uint64 revocationTime; bytes32 refUID; address recipient; address attester; bool revocable; bytes data; }
+        function getAttestation(bytes32 uid) external view returns (Attestation)
+        event Attested(address indexed recipient, address indexed attester, bytes32 uid, bytes32 indexed schemaUID)
+    ]"#,
+);
+
+/// EAS contract to query. Override with EAS_CONTRACT_ADDRESS to point at a
+/// deployment on a local anvil node.
+fn eas_address() -> Result<Address, Box<dyn std::error::Error>> {
+    let address = env::var("EAS_CONTRACT_ADDRESS")
+        .unwrap_or_else(|_| DEFAULT_EAS_ADDRESS.to_string());
+    Ok(address.parse()?)
+}
+
+/// First block scanned for Attested events (EAS_FROM_BLOCK, default 0)
+fn from_block() -> u64 {
+    env::var("EAS_FROM_BLOCK")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Indexer queried for candidates (EAS_GRAPHQL_URL). Set it empty to scan Attested
+/// logs instead, e.g. against a local anvil node with no indexer.
+fn graphql_url() -> Option<String> {
+    match env::var("EAS_GRAPHQL_URL") {
+        Ok(url) if url.trim().is_empty() => None,
+        Ok(url) => Some(url),
+        Err(_) => Some(DEFAULT_EAS_GRAPHQL_URL.to_string()),
+    }
+}
+
+/// Find a valid attestation of `schema_uid` for `recipient` as of `block_number`.
+///
+/// Candidates come from the EAS indexer, or from Attested events (indexed by
+/// recipient, attester and schema) when it is disabled; each is then read back with
+/// getAttestation so revocations and expiry are taken from contract state, not the
+/// indexer or event. Returns the UID of the newest valid one.
+pub async fn find_valid_attestation(
+    provider: &Provider<Http>,
+    schema_uid: &str,
+    attester: Option<&str>,
+    recipient: Address,
+    block_number: U64,
+) -> Result<Option<H256>, Box<dyn std::error::Error>> {
+    let schema: H256 = schema_uid.parse()?;
+    let attester: Option<Address> = attester.map(|a| a.parse()).transpose()?;
+
+    let contract = EAS::new(eas_address()?, provider.clone().into());
+
+    let candidates = match graphql_url() {
+        Some(url) => indexed_candidates(&url, schema, attester, recipient).await?,
+        None => logged_candidates(&contract, schema, attester, recipient, block_number).await?,
+    };
+
+    let block = BlockId::from(block_number);
+    let now = match provider.get_block(block_number).await? {
+        Some(b) => b.timestamp.as_u64(),
+        None => return Ok(None),
+    };
+
+    for uid in candidates {
+        // (uid, schema, time, expirationTime, revocationTime, refUID, recipient, attester, revocable, data)
+        let (_, attested_schema, _, expiration_time, revocation_time, _, attested_recipient, attested_by, _, _) =
+            contract.get_attestation(uid.0).block(block).call().await?;
+
+        let revoked = revocation_time != 0;
+        let expired = expiration_time != 0 && expiration_time <= now;
+        let matches = attested_recipient == recipient
+            && attested_schema == schema.0
+            && (attester.is_none() || attester == Some(attested_by));
+
+        if matches && !revoked && !expired {
+            return Ok(Some(uid));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Newest attestation UIDs for the recipient and schema known to the indexer. Already
+/// revoked ones are left out; everything else is checked on-chain by the caller.
+async fn indexed_candidates(
+    url: &str,
+    schema: H256,
+    attester: Option<Address>,
+    recipient: Address,
+) -> Result<Vec<H256>, Box<dyn std::error::Error>> {
+    let equals = |value: String| serde_json::json!({ "equals": value, "mode": "insensitive" });
+    let mut filter = serde_json::json!({
+        "schemaId": equals(format!("{:#x}", schema)),
+        "recipient": equals(format!("{:#x}", recipient)),
+        "revoked": { "equals": false },
+    });
+    if let Some(attester) = attester {
+        filter["attester"] = equals(format!("{:#x}", attester));
+    }
+
+    let body = serde_json::json!({
+        "query": "query($where: AttestationWhereInput, $take: Int) { attestations(where: $where, orderBy: [{ time: desc }], take: $take) { id } }",
+        "variables": { "where": filter, "take": MAX_CANDIDATES },
+    });
+    let resp = reqwest::Client::new()
+        .post(url)
+        .json(&body)
+        .timeout(Duration::from_secs(GRAPHQL_TIMEOUT_SECS))
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(format!("EAS indexer returned {}", resp.status()).into());
+    }
+
+    let data: serde_json::Value = resp.json().await?;
+    let attestations = data["data"]["attestations"]
+        .as_array()
+        .ok_or("EAS indexer returned no attestations field")?;
+    Ok(attestations
+        .iter()
+        .filter_map(|a| a["id"].as_str()?.parse().ok())
+        .collect())
+}
+
+/// Newest Attested events for the recipient and schema, scanned back from
+/// `block_number` to EAS_FROM_BLOCK in chunks the RPC accepts
+async fn logged_candidates(
+    contract: &EAS<Provider<Http>>,
+    schema: H256,
+    attester: Option<Address>,
+    recipient: Address,
+    block_number: U64,
+) -> Result<Vec<H256>, Box<dyn std::error::Error>> {
+    let mut filter = contract.attested_filter().filter.topic1(H256::from(recipient)).topic3(schema);
+    if let Some(attester) = attester {
+        filter = filter.topic2(H256::from(attester));
+    }
+
+    let mut candidates = Vec::new();
+    log_scanner::scan_backwards(&contract.client(), &[filter], from_block(), block_number.as_u64(), |logs| {
+        // The UID is the only non-indexed field
+        candidates.extend(logs.iter().filter(|l| l.data.len() == 32).map(|l| H256::from_slice(&l.data)));
+        candidates.len() < MAX_CANDIDATES
+    })
+    .await?;
+
+    candidates.truncate(MAX_CANDIDATES);
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    abigen!(
+        SchemaRegistry,
+        r#"[
+            function register(string schema, address resolver, bool revocable) external returns (bytes32)
+        ]"#,
+    );
+
+    abigen!(
+        EASWriter,
+        r#"[
+            struct AttestationRequestData { address recipient; uint64 expirationTime; bool revocable; bytes32 refUID; bytes data; uint256 value; }
+            struct AttestationRequest { bytes32 schema; AttestationRequestData data; }
+            struct RevocationRequestData { bytes32 uid; uint256 value; }
+            struct RevocationRequest { bytes32 schema; RevocationRequestData data; }
+            function attest(AttestationRequest request) external payable returns (bytes32)
+            function revoke(RevocationRequest request) external payable
+        ]"#,
+    );
+
+    /// Schema registry predeploy on Base
+    const SCHEMA_REGISTRY_ADDRESS: &str = "0x4200000000000000000000000000000000000020";
+    /// First default anvil account
+    const ANVIL_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    /// Runs against anvil forked from Base, where the EAS predeploys exist:
+    ///   anvil --fork-url https://mainnet.base.org
+    ///   ANVIL_RPC_URL=http://127.0.0.1:8545 cargo test -- --ignored eas
+    #[tokio::test]
+    #[ignore = "needs an anvil node forked from Base (ANVIL_RPC_URL)"]
+    async fn test_find_valid_attestation_on_anvil() {
+        let url = env::var("ANVIL_RPC_URL").expect("ANVIL_RPC_URL");
+        let provider = Provider::<Http>::try_from(url.as_str()).unwrap();
+        let chain_id = provider.get_chainid().await.unwrap().as_u64();
+        let wallet: LocalWallet = ANVIL_KEY.parse::<LocalWallet>().unwrap().with_chain_id(chain_id);
+        let attester = wallet.address();
+        let client = Arc::new(SignerMiddleware::new(provider.clone(), wallet));
+
+        // Scan logs from the fork point rather than ask the Base indexer
+        let fork_block = provider.get_block_number().await.unwrap();
+        env::set_var("EAS_GRAPHQL_URL", "");
+        env::set_var("EAS_FROM_BLOCK", fork_block.to_string());
+
+        let registry = SchemaRegistry::new(SCHEMA_REGISTRY_ADDRESS.parse::<Address>().unwrap(), client.clone());
+        let register = registry.register(format!("bool verified_{}", fork_block), Address::zero(), true);
+        let schema = register.call().await.unwrap();
+        register.send().await.unwrap().await.unwrap();
+
+        let recipient = Address::repeat_byte(0x42);
+        let eas = EASWriter::new(eas_address().unwrap(), client.clone());
+        let attest = eas.attest(AttestationRequest {
+            schema,
+            data: AttestationRequestData {
+                recipient,
+                expiration_time: 0,
+                revocable: true,
+                ref_uid: [0u8; 32],
+                data: ethers::abi::encode(&[ethers::abi::Token::Bool(true)]).into(),
+                value: U256::zero(),
+            },
+        });
+        let uid = attest.call().await.unwrap();
+        attest.send().await.unwrap().await.unwrap();
+
+        let schema_uid = format!("{:#x}", H256::from(schema));
+        let head = provider.get_block_number().await.unwrap();
+        let found = find_valid_attestation(&provider, &schema_uid, None, recipient, head).await.unwrap();
+        assert_eq!(found, Some(H256::from(uid)));
+
+        let by_attester = format!("{:#x}", attester);
+        let found = find_valid_attestation(&provider, &schema_uid, Some(&by_attester), recipient, head).await.unwrap();
+        assert_eq!(found, Some(H256::from(uid)));
+
+        let other_attester = format!("{:#x}", Address::repeat_byte(0x99));
+        let found = find_valid_attestation(&provider, &schema_uid, Some(&other_attester), recipient, head).await.unwrap();
+        assert_eq!(found, None);
+
+        eas.revoke(RevocationRequest { schema, data: RevocationRequestData { uid, value: U256::zero() } })
+            .send()
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        let head = provider.get_block_number().await.unwrap();
+        let found = find_valid_attestation(&provider, &schema_uid, None, recipient, head).await.unwrap();
+        assert_eq!(found, None);
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use std::env;
+
+const DEFAULT_MAINNET_RPC_URL: &str = "https://eth.llamarpc.com";
+const DEFAULT_CACHE_TTL_SECS: i64 = 86400;
+const MAX_RECORD_LEN: usize = 2048;
+
+/// Primary ENS name of a wallet and the text records we show on profiles
+#[derive(Debug, Default)]
+pub struct EnsRecords {
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+    pub url: Option<String>,
+    pub twitter: Option<String>,
+    pub github: Option<String>,
+}
+
+pub fn mainnet_provider() -> Result<Provider<Http>> {
+    let rpc_url = env::var("ETH_MAINNET_RPC_URL")
+        .unwrap_or_else(|_| DEFAULT_MAINNET_RPC_URL.to_string());
+    Ok(Provider::<Http>::try_from(rpc_url)?)
+}
+
+/// How long resolved ENS data is served before it is looked up again
+pub fn cache_ttl() -> chrono::Duration {
+    let secs = env::var("ENS_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_CACHE_TTL_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+/// Resolve the wallet's primary name and its text records. `lookup_address` only
+/// returns a name whose forward record points back at the wallet.
+pub async fn resolve_records(provider: &Provider<Http>, wallet: Address) -> Result<EnsRecords> {
+    let name = match provider.lookup_address(wallet).await {
+        Ok(name) if !name.trim().is_empty() => name.trim().to_lowercase(),
+        Ok(_) => return Ok(EnsRecords::default()),
+        Err(e) if is_missing_record(&e) => return Ok(EnsRecords::default()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let avatar = match text_record(provider, &name, "avatar").await? {
+        // NFT avatars ("eip155:1/erc721:…") need the token metadata for a usable URL
+        Some(avatar) if avatar.starts_with("eip155:") => {
+            provider.resolve_avatar(&name).await.ok().map(|url| url.to_string())
+        }
+        avatar => avatar,
+    };
+
+    Ok(EnsRecords {
+        avatar,
+        url: text_record(provider, &name, "url").await?,
+        twitter: text_record(provider, &name, "com.twitter").await?,
+        github: text_record(provider, &name, "com.github").await?,
+        name: Some(name),
+    })
+}
+
+async fn text_record(provider: &Provider<Http>, name: &str, key: &str) -> Result<Option<String>> {
+    match provider.resolve_field(name, key).await {
+        Ok(value) => {
+            let value = value.trim();
+            Ok((!value.is_empty() && value.len() <= MAX_RECORD_LEN).then(|| value.to_string()))
+        }
+        Err(e) if is_missing_record(&e) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// No resolver, no reverse record or a name that isn't owned: not an RPC failure
+fn is_missing_record(e: &ProviderError) -> bool {
+    matches!(e, ProviderError::EnsError(_) | ProviderError::EnsNotOwned(_))
+}
@@ -4,12 +4,13 @@ use std::time::Duration;
 use uuid::Uuid;
 
 use crate::models::feed::{FeedSubscription, TriggerRule};
-use crate::services::feed_service;
+use crate::services::{feed_service, order_service};
 
 const SUBSCRIPTION_REFRESH_SECS: u64 = 60;
 const POLL_LOOP_SLEEP_SECS: u64 = 10;
 const SNAPSHOT_KEEP: i64 = 50;           // keep last 50 snapshots per subscription
 const TRIGGER_COOLDOWN_SECS: i64 = 3600; // 1h cooldown per trigger type
+const CHECKOUT_PRICE_REFRESH_SECS: u64 = 60; // USD prices for shop checkout quotes
 
 /// Spawn the feed poller background task. Call once from main.rs.
 pub fn spawn(pool: PgPool) {
@@ -26,8 +27,21 @@ async fn run_loop(pool: PgPool) {
     let mut subscriptions: Vec<FeedSubscription> = Vec::new();
     // Track when each subscription was last polled
     let mut last_poll_times: HashMap<Uuid, std::time::Instant> = HashMap::new();
+    let mut last_price_refresh: Option<std::time::Instant> = None;
 
     loop {
+        // Keep checkout token prices fresh whether or not any group follows them
+        let prices_due = match last_price_refresh {
+            Some(last) => last.elapsed() >= Duration::from_secs(CHECKOUT_PRICE_REFRESH_SECS),
+            None => true,
+        };
+        if prices_due {
+            if let Err(e) = refresh_checkout_prices(&pool).await {
+                log::warn!("Feed poller: failed to refresh checkout token prices: {}", e);
+            }
+            last_price_refresh = Some(std::time::Instant::now());
+        }
+
         // Refresh active subscriptions periodically
         if last_refresh.elapsed() >= Duration::from_secs(SUBSCRIPTION_REFRESH_SECS) {
             match feed_service::get_active_subscriptions(&pool).await {
@@ -182,6 +196,36 @@ async fn poll_crypto(pool: &PgPool, sub: &FeedSubscription) -> anyhow::Result<()
     Ok(())
 }
 
+/// Fetch the USD price of every token accepted for USD-priced shop items in one call
+async fn refresh_checkout_prices(pool: &PgPool) -> anyhow::Result<()> {
+    let ids: Vec<&str> = order_service::QUOTE_TOKENS.iter().map(|t| t.coingecko_id).collect();
+    let url = format!(
+        "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies=usd",
+        ids.join(",")
+    );
+
+    let mut req = reqwest::Client::new()
+        .get(&url)
+        .header("Accept", "application/json");
+    if let Ok(key) = std::env::var("COINGECKO_API_KEY") {
+        req = req.header("x-cg-demo-api-key", key);
+    }
+    let resp = req.send().await?;
+    if !resp.status().is_success() {
+        anyhow::bail!("CoinGecko simple price returned {}", resp.status());
+    }
+    let prices: HashMap<String, CoinGeckoPrice> = resp.json().await?;
+
+    for id in ids {
+        match prices.get(id).and_then(|p| p.usd) {
+            Some(usd) if usd > 0.0 => feed_service::upsert_token_price(pool, id, usd).await?,
+            _ => log::warn!("Feed poller: no USD price returned for {}", id),
+        }
+    }
+
+    Ok(())
+}
+
 async fn fetch_ohlc_candles(coin_id: &str, api_key: Option<&str>) -> anyhow::Result<serde_json::Value> {
     // CoinGecko OHLC: returns [[timestamp_ms, open, high, low, close], ...]
     // days=1 gives hourly candles for the last 24h
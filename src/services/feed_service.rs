@@ -6,7 +6,7 @@ use crate::models::feed::{
     FeedSubscription, FeedSnapshot, FeedEvent,
     CreateFeedSubscriptionRequest, UpdateFeedSubscriptionRequest,
     FeedSubscriptionResponse, FeedSnapshotResponse, FeedEventResponse,
-    FeedStateResponse,
+    FeedStateResponse, TokenPrice,
 };
 
 // ── Subscriptions ──
@@ -174,6 +174,35 @@ pub async fn prune_snapshots(
     Ok(())
 }
 
+// ── Checkout token prices ──
+
+pub async fn upsert_token_price(
+    pool: &PgPool,
+    coingecko_id: &str,
+    price_usd: f64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"INSERT INTO token_prices (coingecko_id, price_usd, fetched_at)
+           VALUES ($1, $2, NOW())
+           ON CONFLICT (coingecko_id) DO UPDATE SET price_usd = EXCLUDED.price_usd, fetched_at = NOW()"#,
+    )
+    .bind(coingecko_id)
+    .bind(price_usd)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_token_price(
+    pool: &PgPool,
+    coingecko_id: &str,
+) -> Result<Option<TokenPrice>, sqlx::Error> {
+    sqlx::query_as::<_, TokenPrice>("SELECT * FROM token_prices WHERE coingecko_id = $1")
+        .bind(coingecko_id)
+        .fetch_optional(pool)
+        .await
+}
+
 // ── Events ──
 
 pub async fn insert_event(
@@ -0,0 +1,146 @@
+use crate::models::{GatePassClaims, GatePassJwk, GatePassPublicKeyResponse};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::{DecodePrivateKey, EncodePublicKey, LineEnding};
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use sha3::{Digest, Keccak256};
+
+pub const GATE_PASS_ISSUER: &str = "blocchat-backend";
+const GATE_PASS_TTL_MINUTES: i64 = 10;
+
+/// Server key used to sign gate passes, loaded once at startup
+pub struct GatePassKeys {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    kid: String,
+    public_key_pem: String,
+    n: String,
+    e: String,
+}
+
+/// Load the RSA signing key from a PEM file (PKCS#1 or PKCS#8)
+pub fn load_keys(path: &str) -> Result<GatePassKeys> {
+    let pem = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read gate pass key {}: {}", path, e))?;
+
+    let private_key = RsaPrivateKey::from_pkcs1_pem(&pem)
+        .or_else(|_| RsaPrivateKey::from_pkcs8_pem(&pem))
+        .map_err(|e| anyhow!("Invalid RSA private key: {}", e))?;
+    let public_key = private_key.to_public_key();
+
+    let n_bytes = public_key.n().to_bytes_be();
+    let n = URL_SAFE_NO_PAD.encode(&n_bytes);
+    let e = URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be());
+
+    // Key id: first 8 bytes of keccak256(modulus)
+    let mut hasher = Keccak256::new();
+    hasher.update(&n_bytes);
+    let kid = hex::encode(&hasher.finalize()[..8]);
+
+    let public_key_pem = public_key
+        .to_public_key_pem(LineEnding::LF)
+        .map_err(|e| anyhow!("Failed to encode public key: {}", e))?;
+
+    Ok(GatePassKeys {
+        encoding_key: EncodingKey::from_rsa_pem(pem.as_bytes())?,
+        decoding_key: DecodingKey::from_rsa_components(&n, &e)?,
+        kid,
+        public_key_pem,
+        n,
+        e,
+    })
+}
+
+/// Sign a short-lived gate pass. Returns the token and its expiry.
+pub fn issue_pass(
+    keys: &GatePassKeys,
+    conversation_id: &str,
+    wallet_address: &str,
+    gate_hash: &str,
+    block_number: u64,
+) -> Result<(String, chrono::DateTime<Utc>)> {
+    let now = Utc::now();
+    let expires_at = now + Duration::minutes(GATE_PASS_TTL_MINUTES);
+
+    let claims = GatePassClaims {
+        iss: GATE_PASS_ISSUER.to_string(),
+        sub: wallet_address.to_lowercase(),
+        conversation_id: conversation_id.to_string(),
+        gate_hash: gate_hash.to_string(),
+        block_number,
+        chain_id: chain_id(),
+        iat: now.timestamp(),
+        exp: expires_at.timestamp(),
+        jti: uuid::Uuid::new_v4().to_string(),
+    };
+
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(keys.kid.clone());
+
+    let token = jsonwebtoken::encode(&header, &claims, &keys.encoding_key)?;
+    Ok((token, expires_at))
+}
+
+fn chain_id() -> u64 {
+    std::env::var("BASE_CHAIN_ID")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(8453)
+}
+
+/// Check signature, issuer and expiry of a gate pass and return its claims
+pub fn decode_pass(keys: &GatePassKeys, token: &str) -> Result<GatePassClaims> {
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_issuer(&[GATE_PASS_ISSUER]);
+
+    let data = jsonwebtoken::decode::<GatePassClaims>(token, &keys.decoding_key, &validation)
+        .map_err(|e| anyhow!("{}", e))?;
+    Ok(data.claims)
+}
+
+pub fn public_key_response(keys: &GatePassKeys) -> GatePassPublicKeyResponse {
+    GatePassPublicKeyResponse {
+        issuer: GATE_PASS_ISSUER.to_string(),
+        alg: "RS256".to_string(),
+        kid: keys.kid.clone(),
+        public_key_pem: keys.public_key_pem.clone(),
+        jwk: GatePassJwk {
+            kty: "RSA".to_string(),
+            key_use: "sig".to_string(),
+            alg: "RS256".to_string(),
+            kid: keys.kid.clone(),
+            n: keys.n.clone(),
+            e: keys.e.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_and_decode_pass() {
+        let keys = load_keys(concat!(env!("CARGO_MANIFEST_DIR"), "/blocchat-key.pem")).unwrap();
+        let (token, _) = issue_pass(&keys, "conv-1", "0xABC", "0xhash", 123).unwrap();
+
+        let claims = decode_pass(&keys, &token).unwrap();
+        assert_eq!(claims.sub, "0xabc");
+        assert_eq!(claims.conversation_id, "conv-1");
+        assert_eq!(claims.block_number, 123);
+
+        // Any change to the payload breaks the signature
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let tampered = URL_SAFE_NO_PAD.encode(
+            String::from_utf8(URL_SAFE_NO_PAD.decode(parts[1]).unwrap())
+                .unwrap()
+                .replace("conv-1", "conv-2"),
+        );
+        parts[1] = &tampered;
+        assert!(decode_pass(&keys, &parts.join(".")).is_err());
+    }
+}
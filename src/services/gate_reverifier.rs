@@ -0,0 +1,227 @@
+use anyhow::{anyhow, Result};
+use reqwest::{redirect, Url};
+use sqlx::PgPool;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use crate::models::VerifyTokenGateRequest;
+use crate::services::token_gate_service;
+
+const LOOP_SLEEP_SECS: u64 = 300;
+const RECHECK_INTERVAL_SECS: i64 = 21600; // re-check each member every 6h
+const BATCH_SIZE: i64 = 100;              // members checked per pass
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// Spawn the token gate re-verification background task. Call once from main.rs.
+pub fn spawn(pool: PgPool) {
+    tokio::spawn(async move {
+        log::info!("🔐 Gate re-verifier starting...");
+        run_loop(pool).await;
+    });
+}
+
+async fn run_loop(pool: PgPool) {
+    loop {
+        match token_gate_service::get_members_due_for_check(&pool, RECHECK_INTERVAL_SECS, BATCH_SIZE)
+            .await
+        {
+            Ok(members) => {
+                if !members.is_empty() {
+                    log::info!("🔐 Re-verifying {} gated member(s)", members.len());
+                }
+                for member in &members {
+                    recheck_member(&pool, &member.conversation_id, &member.wallet_address).await;
+                }
+            }
+            Err(e) => log::error!("Gate re-verifier: failed to load members: {}", e),
+        }
+
+        send_eviction_webhooks(&pool).await;
+
+        tokio::time::sleep(Duration::from_secs(LOOP_SLEEP_SECS)).await;
+    }
+}
+
+async fn recheck_member(pool: &PgPool, conversation_id: &str, wallet_address: &str) {
+    let req = VerifyTokenGateRequest {
+        conversation_id: conversation_id.to_string(),
+        wallet_address: wallet_address.to_string(),
+        include_pass: false,
+    };
+
+    // Stringify the error right away: the boxed error is not Send
+    let result = token_gate_service::verify_token_gates(pool, req)
+        .await
+        .map_err(|e| e.to_string());
+
+    let recorded = match result {
+        Ok(response) => {
+            token_gate_service::record_membership_check(
+                pool,
+                conversation_id,
+                wallet_address,
+                response.allowed,
+                &response.requirements_met,
+            )
+            .await
+        }
+        Err(e) => {
+            log::warn!(
+                "Gate re-verifier: check failed for {} in {}: {}",
+                wallet_address, conversation_id, e
+            );
+            token_gate_service::record_membership_error(pool, conversation_id, wallet_address, &e)
+                .await
+        }
+    };
+
+    if let Err(e) = recorded {
+        log::error!("Gate re-verifier: failed to record status: {}", e);
+    }
+}
+
+/// POST newly due "no longer qualifies" lists to each conversation's webhook
+async fn send_eviction_webhooks(pool: &PgPool) {
+    let pending = match token_gate_service::get_pending_eviction_webhooks(pool).await {
+        Ok(p) => p,
+        Err(e) => {
+            log::error!("Gate re-verifier: failed to load pending webhooks: {}", e);
+            return;
+        }
+    };
+
+    for (conversation_id, webhook_url) in pending {
+        // Checked again on every send: the host may have been re-pointed since it was saved
+        let client = match webhook_client(&webhook_url).await {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("Gate re-verifier: skipping webhook for {}: {}", conversation_id, e);
+                continue;
+            }
+        };
+
+        let report = match token_gate_service::get_eviction_report(pool, &conversation_id, true).await {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Gate re-verifier: failed to build report for {}: {}", conversation_id, e);
+                continue;
+            }
+        };
+        if report.members.is_empty() {
+            continue;
+        }
+
+        let sent = client
+            .post(&webhook_url)
+            .json(&report)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status());
+
+        match sent {
+            Ok(_) => {
+                let wallets: Vec<String> =
+                    report.members.iter().map(|m| m.wallet_address.clone()).collect();
+                if let Err(e) =
+                    token_gate_service::mark_evictions_notified(pool, &conversation_id, &wallets).await
+                {
+                    log::error!("Gate re-verifier: failed to mark notified: {}", e);
+                }
+                log::info!(
+                    "🔐 Sent eviction report for {} ({} member(s))",
+                    conversation_id,
+                    wallets.len()
+                );
+            }
+            Err(e) => log::warn!(
+                "Gate re-verifier: webhook for {} failed: {}",
+                conversation_id, e
+            ),
+        }
+    }
+}
+
+/// Check a webhook URL before it is saved or called. Reports list member wallets, so
+/// only https URLs whose host resolves to public addresses are allowed. Returns the
+/// resolved addresses for the caller to connect to.
+pub async fn check_webhook_url(webhook_url: &str) -> Result<(Url, Vec<SocketAddr>)> {
+    let url = Url::parse(webhook_url).map_err(|_| anyhow!("Webhook URL is not a valid URL"))?;
+    if url.scheme() != "https" {
+        return Err(anyhow!("Webhook URL must use https"));
+    }
+    let host = url.host_str().ok_or_else(|| anyhow!("Webhook URL has no host"))?;
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.trim_matches(['[', ']']), port))
+        .await
+        .map_err(|_| anyhow!("Webhook host does not resolve"))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(anyhow!("Webhook host does not resolve"));
+    }
+    if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err(anyhow!("Webhook host must not be a private, loopback or link-local address"));
+    }
+    Ok((url, addrs))
+}
+
+/// Client pinned to the addresses checked above, so DNS cannot change between the
+/// check and the request, and without redirects, which could lead anywhere
+async fn webhook_client(webhook_url: &str) -> Result<reqwest::Client> {
+    let (url, addrs) = check_webhook_url(webhook_url).await?;
+    let mut builder = reqwest::Client::builder()
+        .redirect(redirect::Policy::none())
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS));
+    if let Some(domain) = url.domain() {
+        builder = builder.resolve_to_addrs(domain, &addrs);
+    }
+    Ok(builder.build()?)
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))) // carrier-grade NAT
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00  // unique local
+                || (first & 0xffc0) == 0xfe80) // link-local
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_webhook_url() {
+        assert!(check_webhook_url("http://93.184.216.34/hook").await.is_err());
+        assert!(check_webhook_url("https://127.0.0.1/hook").await.is_err());
+        assert!(check_webhook_url("https://localhost/hook").await.is_err());
+        assert!(check_webhook_url("https://10.0.0.5/hook").await.is_err());
+        assert!(check_webhook_url("https://169.254.169.254/latest/meta-data").await.is_err());
+        assert!(check_webhook_url("https://[::1]/hook").await.is_err());
+        assert!(check_webhook_url("https://[::ffff:192.168.1.1]/hook").await.is_err());
+        assert!(check_webhook_url("https://[fd00::1]/hook").await.is_err());
+
+        let (_, addrs) = check_webhook_url("https://93.184.216.34/hook").await.unwrap();
+        assert_eq!(addrs, vec!["93.184.216.34:443".parse().unwrap()]);
+    }
+}
@@ -1,5 +1,8 @@
 use sqlx::PgPool;
 use crate::models::group::{PublicGroup, CreatePublicGroupRequest, UpdatePublicGroupRequest};
+use crate::models::SearchHighlight;
+
+const POPULARITY_WEIGHT: f32 = 0.05; // Search rank boost per ln(1 + member_count)
 
 pub async fn register_group(
     pool: &PgPool,
@@ -85,20 +88,51 @@ pub async fn delete_group(
     Ok(())
 }
 
+#[derive(sqlx::FromRow)]
+struct GroupSearchRow {
+    #[sqlx(flatten)]
+    group: PublicGroup,
+    #[sqlx(flatten)]
+    highlight: SearchHighlight,
+}
+
+/// Fuzzy search over public group names and descriptions, ranked by the better
+/// matching attribute plus a member count boost. Ties are broken by creation time
+/// and id so offsets page stably.
 pub async fn search_groups(
     pool: &PgPool,
     query: &str,
     limit: i64,
-) -> Result<Vec<PublicGroup>, sqlx::Error> {
-    sqlx::query_as::<_, PublicGroup>(
-        r#"SELECT * FROM public_groups
-           WHERE is_public = true
-             AND (name ILIKE $1 OR description ILIKE $1)
-           ORDER BY member_count DESC, created_at DESC
-           LIMIT $2"#,
+    offset: i64,
+) -> Result<Vec<(PublicGroup, SearchHighlight)>, sqlx::Error> {
+    // The outer WHERE mirrors the per-field matches so the trigram indexes are used
+    let rows = sqlx::query_as::<_, GroupSearchRow>(
+        r#"SELECT g.*, m.match_field, m.match_value, m.match_kind,
+                  m.score + $4 * LN(1 + GREATEST(g.member_count, 0)) AS rank
+           FROM public_groups g
+           CROSS JOIN LATERAL (
+               SELECT f.field AS match_field, f.value AS match_value,
+                      search_match_kind(f.value, $1) AS match_kind,
+                      search_match_score(f.value, $1) AS score
+               FROM (VALUES ('name', g.name), ('description', g.description)) AS f(field, value)
+               WHERE search_match_kind(f.value, $1) IS NOT NULL
+               ORDER BY score DESC
+               LIMIT 1
+           ) m
+           WHERE g.is_public = true
+             AND (
+                 g.name % $1 OR $1 <% g.name OR g.name ILIKE search_like_pattern($1)
+                 OR g.description % $1 OR $1 <% g.description OR g.description ILIKE search_like_pattern($1)
+             )
+           ORDER BY rank DESC, g.created_at DESC, g.id
+           LIMIT $2 OFFSET $3"#,
     )
-    .bind(format!("%{}%", query))
+    .bind(query.trim())
     .bind(limit)
+    .bind(offset)
+    .bind(POPULARITY_WEIGHT)
     .fetch_all(pool)
-    .await
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.group, row.highlight)).collect())
 }
@@ -0,0 +1,227 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use regex::Regex;
+use sha3::{Digest, Sha3_256};
+use std::env;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+use crate::models::UploadedImage;
+
+pub const MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+const MAX_SOURCE_DIMENSION: u32 = 8192; // Guards against decompression bombs
+const FULL_SIZE: u32 = 1024; // Longest side of the stored image
+const THUMBNAIL_SIZES: [u32; 2] = [256, 64]; // Square, center-cropped
+const JPEG_QUALITY: u8 = 85;
+
+/// Cache-Control for stored images. Keys are content addressed, so they never change.
+pub const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+// ── Storage ──
+
+/// Where processed images live. Keys look like `images/<hash>/<variant>.<ext>`.
+#[async_trait]
+pub trait ImageStorage: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<()>;
+    /// None when nothing is stored under `key`
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    /// Public URL clients load the image from
+    fn url(&self, key: &str) -> String;
+}
+
+/// Files under IMAGE_STORAGE_DIR, served by GET /api/uploads/{key}
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+#[async_trait]
+impl ImageStorage for LocalStorage {
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> Result<()> {
+        let path = self.root.join(key);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        // Write then rename so a half-written file is never served
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, bytes).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.root.join(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", public_base_url(), key)
+    }
+}
+
+/// Storage backend picked by IMAGE_STORAGE. Only `local` exists so far; an
+/// S3-compatible backend slots in as another `ImageStorage` implementation.
+pub fn storage_from_env() -> Result<Arc<dyn ImageStorage>> {
+    match env::var("IMAGE_STORAGE").unwrap_or_else(|_| "local".to_string()).as_str() {
+        "local" => {
+            let root = PathBuf::from(env::var("IMAGE_STORAGE_DIR").unwrap_or_else(|_| "uploads".to_string()));
+            std::fs::create_dir_all(&root)?;
+            Ok(Arc::new(LocalStorage { root }))
+        }
+        other => Err(anyhow!("Unsupported IMAGE_STORAGE backend '{}'", other)),
+    }
+}
+
+/// Base of every uploaded image URL, without a trailing slash
+pub fn public_base_url() -> String {
+    env::var("IMAGE_PUBLIC_BASE_URL")
+        .unwrap_or_else(|_| "http://localhost:8080/api/uploads".to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Whether `url` points at an image uploaded here. Avatars and group images must.
+pub fn is_uploaded_image_url(url: &str) -> bool {
+    url.strip_prefix(&public_base_url())
+        .and_then(|rest| rest.strip_prefix('/'))
+        .is_some_and(is_valid_key)
+}
+
+/// Keys this service produces; anything else (e.g. `..`) is rejected before storage
+pub fn is_valid_key(key: &str) -> bool {
+    static KEY: OnceLock<Regex> = OnceLock::new();
+    KEY.get_or_init(|| Regex::new(r"^images/[0-9a-f]{32}/(full|256|64)\.(jpg|png)$").unwrap())
+        .is_match(key)
+}
+
+pub fn content_type_for(key: &str) -> &'static str {
+    if key.ends_with(".png") {
+        "image/png"
+    } else {
+        "image/jpeg"
+    }
+}
+
+// ── Processing ──
+
+struct Variant {
+    name: String,
+    bytes: Vec<u8>,
+}
+
+/// Validate, process and store an uploaded image. The upload is decoded and
+/// re-encoded, which drops EXIF and other metadata (orientation is applied first).
+pub async fn upload_image(storage: &dyn ImageStorage, data: Vec<u8>) -> Result<UploadedImage> {
+    if data.is_empty() {
+        return Err(anyhow!("The image is empty"));
+    }
+    if data.len() > MAX_UPLOAD_BYTES {
+        return Err(anyhow!("Images can be at most {} MB", MAX_UPLOAD_BYTES / 1024 / 1024));
+    }
+
+    let hash = hex::encode(Sha3_256::digest(&data));
+    let (variants, extension, width, height) = tokio::task::spawn_blocking(move || process(&data)).await??;
+
+    let content_type = if extension == "png" { "image/png" } else { "image/jpeg" };
+    let key = |name: &str| format!("images/{}/{}.{}", &hash[..32], name, extension);
+    for variant in variants {
+        storage.put(&key(&variant.name), variant.bytes, content_type).await?;
+    }
+
+    Ok(UploadedImage {
+        url: storage.url(&key("full")),
+        thumbnails: THUMBNAIL_SIZES
+            .iter()
+            .map(|size| (size.to_string(), storage.url(&key(&size.to_string()))))
+            .collect(),
+        width,
+        height,
+        content_type: content_type.to_string(),
+    })
+}
+
+/// Decode, orient and resize into the full image and thumbnails. Images with an
+/// alpha channel are stored as PNG, everything else as JPEG.
+fn process(data: &[u8]) -> Result<(Vec<Variant>, &'static str, u32, u32)> {
+    let mut reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+    match reader.format() {
+        Some(ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP | ImageFormat::Gif) => {}
+        _ => return Err(anyhow!("Only JPEG, PNG, WebP and GIF images are accepted")),
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(|e| anyhow!("Could not read the image: {}", e))?;
+    let orientation = decoder.orientation()?;
+    // GIFs keep only their first frame
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| anyhow!("Could not read the image: {}", e))?;
+    image.apply_orientation(orientation);
+
+    let has_alpha = image.color().has_alpha();
+    let extension = if has_alpha { "png" } else { "jpg" };
+    let encode = |img: &DynamicImage| encode(img, has_alpha);
+
+    let full = if image.width() > FULL_SIZE || image.height() > FULL_SIZE {
+        image.resize(FULL_SIZE, FULL_SIZE, FilterType::Lanczos3)
+    } else {
+        image.clone()
+    };
+    let mut variants = vec![Variant { name: "full".to_string(), bytes: encode(&full)? }];
+    for size in THUMBNAIL_SIZES {
+        let thumbnail = image.resize_to_fill(size, size, FilterType::Lanczos3);
+        variants.push(Variant { name: size.to_string(), bytes: encode(&thumbnail)? });
+    }
+
+    Ok((variants, extension, full.width(), full.height()))
+}
+
+fn encode(image: &DynamicImage, has_alpha: bool) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    if has_alpha {
+        DynamicImage::ImageRgba8(image.to_rgba8()).write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    } else {
+        JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY).encode_image(&image.to_rgb8())?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_key() {
+        assert!(is_valid_key("images/0123456789abcdef0123456789abcdef/full.jpg"));
+        assert!(is_valid_key("images/0123456789abcdef0123456789abcdef/64.png"));
+
+        assert!(!is_valid_key("images/../../etc/passwd"));
+        assert!(!is_valid_key("images/0123456789abcdef0123456789abcdef/full.svg"));
+        assert!(!is_valid_key("images/0123456789ABCDEF0123456789ABCDEF/full.jpg"));
+    }
+
+    #[test]
+    fn test_process_strips_metadata_and_resizes() {
+        let source = DynamicImage::new_rgb8(1200, 600);
+        let mut png = Vec::new();
+        source.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+
+        let (variants, extension, width, height) = process(&png).unwrap();
+        assert_eq!(extension, "jpg");
+        assert_eq!((width, height), (1024, 512));
+        assert_eq!(variants.len(), 1 + THUMBNAIL_SIZES.len());
+
+        let thumbnail = image::load_from_memory(&variants[1].bytes).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (256, 256));
+
+        assert!(process(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>").is_err());
+    }
+}
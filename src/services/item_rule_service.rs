@@ -0,0 +1,277 @@
+use crate::{
+    db::DbPool,
+    models::{
+        ItemEligibilityResponse, ItemRuleRequest, ItemRuleResponse, ItemRulesResponse, RequirementStatus,
+        SetItemRulesRequest, ShopItemRule, TokenRequirement,
+    },
+    services::{delivery_service, token_gate_service},
+};
+use anyhow::{anyhow, Result};
+use ethers::prelude::{Http, Middleware, Provider, U64};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const MAX_DISCOUNT_TIERS: usize = 5;
+const MAX_REQUIREMENTS_PER_RULE: usize = 10;
+const MAX_RULE_NAME_LEN: usize = 100;
+
+/// Outcome of an item's holder rules for one wallet
+pub struct Eligibility {
+    pub allowed: bool,
+    pub gate_name: Option<String>,
+    /// Best discount tier met, else the gate that admitted the wallet
+    pub applied: Option<AppliedRule>,
+    pub gate_requirements: Vec<RequirementStatus>,
+}
+
+pub struct AppliedRule {
+    pub rule_id: Uuid,
+    pub name: String,
+    pub discount_bps: i32,
+}
+
+// ── Seller setup ──
+
+pub async fn set_item_rules(
+    pool: &DbPool,
+    item_id: &Uuid,
+    seller_address: &str,
+    req: SetItemRulesRequest,
+) -> Result<ItemRulesResponse> {
+    if req.discounts.len() > MAX_DISCOUNT_TIERS {
+        return Err(anyhow!("An item can have at most {} discount tiers", MAX_DISCOUNT_TIERS));
+    }
+    if let Some(gate) = &req.gate {
+        validate_rule(gate)?;
+    }
+    for tier in &req.discounts {
+        validate_rule(tier)?;
+        if !(1..=10_000).contains(&tier.discount_bps) {
+            return Err(anyhow!("Discount '{}' needs discount_bps between 1 and 10000", tier.name));
+        }
+    }
+    delivery_service::load_seller_item(pool, item_id, seller_address).await?;
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM shop_item_rules WHERE item_id = $1")
+        .bind(item_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let rules = req
+        .gate
+        .into_iter()
+        .map(|rule| ("gate", rule))
+        .chain(req.discounts.into_iter().map(|rule| ("discount", rule)));
+    for (rule_type, rule) in rules {
+        let requirements: Vec<TokenRequirement> = rule
+            .requirements
+            .into_iter()
+            .map(token_gate_service::normalize_requirement)
+            .collect();
+        sqlx::query(
+            r#"
+            INSERT INTO shop_item_rules (item_id, rule_type, name, operator, requirements, discount_bps)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(item_id)
+        .bind(rule_type)
+        .bind(rule.name.trim())
+        .bind(&rule.operator)
+        .bind(serde_json::to_value(&requirements)?)
+        .bind(if rule_type == "discount" { rule.discount_bps } else { 0 })
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    get_item_rules(pool, item_id).await
+}
+
+fn validate_rule(rule: &ItemRuleRequest) -> Result<()> {
+    let name = rule.name.trim();
+    if name.is_empty() || name.len() > MAX_RULE_NAME_LEN {
+        return Err(anyhow!("Rule names must be 1-{} characters", MAX_RULE_NAME_LEN));
+    }
+    if rule.operator != "AND" && rule.operator != "OR" {
+        return Err(anyhow!("Rule operator must be AND or OR"));
+    }
+    if rule.requirements.is_empty() || rule.requirements.len() > MAX_REQUIREMENTS_PER_RULE {
+        return Err(anyhow!("Rule '{}' needs 1-{} requirements", name, MAX_REQUIREMENTS_PER_RULE));
+    }
+    for requirement in &rule.requirements {
+        token_gate_service::validate_requirement(requirement)
+            .map_err(|e| anyhow!("Rule '{}': {}", name, e))?;
+    }
+    Ok(())
+}
+
+pub async fn get_item_rules(pool: &DbPool, item_id: &Uuid) -> Result<ItemRulesResponse> {
+    // Surface a missing item as RowNotFound rather than an empty rule set
+    sqlx::query("SELECT 1 FROM shop_items WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(pool)
+        .await?;
+
+    let rules = load_rules(pool, &[*item_id]).await?;
+    let mut response = ItemRulesResponse {
+        item_id: item_id.to_string(),
+        gate: None,
+        discounts: vec![],
+    };
+    for rule in rules {
+        if rule.rule_type == "gate" {
+            response.gate = Some(ItemRuleResponse::from(rule));
+        } else {
+            response.discounts.push(ItemRuleResponse::from(rule));
+        }
+    }
+    Ok(response)
+}
+
+/// Rules for the given items, discount tiers largest first
+async fn load_rules(pool: &DbPool, item_ids: &[Uuid]) -> Result<Vec<ShopItemRule>> {
+    let rules = sqlx::query_as::<_, ShopItemRule>(
+        r#"
+        SELECT * FROM shop_item_rules
+        WHERE item_id = ANY($1)
+        ORDER BY item_id, rule_type DESC, discount_bps DESC, created_at ASC
+        "#,
+    )
+    .bind(item_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rules)
+}
+
+// ── Evaluation ──
+
+pub async fn check_eligibility(
+    pool: &DbPool,
+    item_id: &Uuid,
+    wallet_address: &str,
+) -> Result<ItemEligibilityResponse> {
+    sqlx::query("SELECT 1 FROM shop_items WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(pool)
+        .await?;
+
+    let mut results = evaluate_items(pool, &[*item_id], wallet_address).await?;
+    let eligibility = results.remove(item_id);
+
+    Ok(match eligibility {
+        Some(e) => ItemEligibilityResponse {
+            item_id: item_id.to_string(),
+            wallet_address: wallet_address.to_string(),
+            allowed: e.allowed,
+            rule_id: e.applied.as_ref().map(|r| r.rule_id.to_string()),
+            rule_name: e.applied.as_ref().map(|r| r.name.clone()),
+            discount_bps: e.applied.as_ref().map_or(0, |r| r.discount_bps),
+            gate_requirements: e.gate_requirements,
+        },
+        None => ItemEligibilityResponse {
+            item_id: item_id.to_string(),
+            wallet_address: wallet_address.to_string(),
+            allowed: true,
+            rule_id: None,
+            rule_name: None,
+            discount_bps: 0,
+            gate_requirements: vec![],
+        },
+    })
+}
+
+/// Evaluate holder rules for several items at one block. Items without rules are
+/// left out of the result, so checkouts without gated items never touch the RPC.
+pub async fn evaluate_items(
+    pool: &DbPool,
+    item_ids: &[Uuid],
+    wallet_address: &str,
+) -> Result<HashMap<Uuid, Eligibility>> {
+    let rules = load_rules(pool, item_ids).await?;
+    if rules.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let provider = token_gate_service::base_provider().map_err(|e| anyhow!("{}", e))?;
+    let block_number = provider.get_block_number().await?;
+
+    let mut by_item: HashMap<Uuid, Vec<ShopItemRule>> = HashMap::new();
+    for rule in rules {
+        by_item.entry(rule.item_id).or_default().push(rule);
+    }
+
+    let mut results = HashMap::new();
+    for (item_id, rules) in by_item {
+        let eligibility = evaluate_item(pool, &provider, rules, wallet_address, block_number).await?;
+        results.insert(item_id, eligibility);
+    }
+    Ok(results)
+}
+
+/// The gate decides whether the wallet may buy at all; discount tiers are tried
+/// largest first and the first one met applies
+async fn evaluate_item(
+    pool: &DbPool,
+    provider: &Provider<Http>,
+    rules: Vec<ShopItemRule>,
+    wallet_address: &str,
+    block_number: U64,
+) -> Result<Eligibility> {
+    let mut eligibility = Eligibility {
+        allowed: true,
+        gate_name: None,
+        applied: None,
+        gate_requirements: vec![],
+    };
+
+    let (gates, tiers): (Vec<_>, Vec<_>) = rules.into_iter().partition(|r| r.rule_type == "gate");
+    if let Some(gate) = gates.into_iter().next() {
+        let (met, statuses) = evaluate_rule(pool, provider, &gate, wallet_address, block_number).await?;
+        eligibility.allowed = met;
+        eligibility.gate_name = Some(gate.name.clone());
+        eligibility.gate_requirements = statuses;
+        if !met {
+            return Ok(eligibility);
+        }
+        eligibility.applied = Some(AppliedRule { rule_id: gate.id, name: gate.name, discount_bps: 0 });
+    }
+
+    for tier in tiers {
+        let (met, _) = evaluate_rule(pool, provider, &tier, wallet_address, block_number).await?;
+        if met {
+            eligibility.applied = Some(AppliedRule {
+                rule_id: tier.id,
+                name: tier.name,
+                discount_bps: tier.discount_bps,
+            });
+            break;
+        }
+    }
+
+    Ok(eligibility)
+}
+
+async fn evaluate_rule(
+    pool: &DbPool,
+    provider: &Provider<Http>,
+    rule: &ShopItemRule,
+    wallet_address: &str,
+    block_number: U64,
+) -> Result<(bool, Vec<RequirementStatus>)> {
+    let requirements: Vec<TokenRequirement> = serde_json::from_value(rule.requirements.clone())?;
+    token_gate_service::evaluate_requirements(
+        pool,
+        provider,
+        requirements,
+        &rule.operator,
+        wallet_address,
+        block_number,
+    )
+    .await
+    .map_err(|e| anyhow!("Could not check '{}' for this wallet: {}", rule.name, e))
+}
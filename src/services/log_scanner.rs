@@ -0,0 +1,73 @@
+use ethers::prelude::*;
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Smallest chunk tried before a rejected eth_getLogs is treated as a real error
+const MIN_CHUNK_BLOCKS: u64 = 1_000;
+
+/// Block range of one eth_getLogs call, shared by every scan. Starts at
+/// LOG_SCAN_CHUNK_BLOCKS (default: the whole range) and halves each time the node
+/// rejects a call, so a capped public RPC is only probed once.
+static CHUNK_BLOCKS: AtomicU64 = AtomicU64::new(0);
+
+fn chunk_blocks() -> u64 {
+    match CHUNK_BLOCKS.load(Ordering::Relaxed) {
+        0 => env::var("LOG_SCAN_CHUNK_BLOCKS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n| *n >= MIN_CHUNK_BLOCKS)
+            .unwrap_or(u64::MAX),
+        n => n,
+    }
+}
+
+/// Scan `from_block..=to_block` newest first. Each chunk runs every filter over the
+/// same blocks and hands `visit` the merged logs, newest first; `visit` returns false
+/// to stop early, so a match near the head never reads older history.
+pub async fn scan_backwards<F>(
+    provider: &Provider<Http>,
+    filters: &[Filter],
+    from_block: u64,
+    to_block: u64,
+    mut visit: F,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: FnMut(&[Log]) -> bool,
+{
+    let mut end = to_block;
+    while end >= from_block {
+        let chunk = chunk_blocks();
+        let start = end.saturating_sub(chunk - 1).max(from_block);
+
+        let mut logs = Vec::new();
+        let mut rejected = None;
+        for filter in filters {
+            let filter = filter.clone().from_block(start).to_block(end);
+            match provider.get_logs(&filter).await {
+                Ok(found) => logs.extend(found),
+                Err(e) => {
+                    rejected = Some(e);
+                    break;
+                }
+            }
+        }
+
+        if let Some(e) = rejected {
+            let range = end - start + 1;
+            if range <= MIN_CHUNK_BLOCKS {
+                return Err(e.into());
+            }
+            let smaller = (range / 2).max(MIN_CHUNK_BLOCKS);
+            log::debug!("eth_getLogs over {} blocks failed ({}), retrying with {}", range, e, smaller);
+            CHUNK_BLOCKS.store(smaller, Ordering::Relaxed);
+            continue;
+        }
+
+        logs.sort_by_key(|l| std::cmp::Reverse((l.block_number, l.log_index)));
+        if !visit(&logs) || start == 0 {
+            break;
+        }
+        end = start - 1;
+    }
+    Ok(())
+}
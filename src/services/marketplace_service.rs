@@ -0,0 +1,197 @@
+use crate::{
+    db::DbPool,
+    models::{
+        ItemResponse, MarketplaceCategory, MarketplaceGroup, MarketplaceItemResponse, MarketplaceSearchQuery,
+        MarketplaceSearchResponse, MarketplaceShop, ShopItem,
+    },
+    services::{order_service, shop_service},
+};
+use anyhow::{anyhow, Result};
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+const MAX_QUERY_LEN: usize = 200;
+
+#[derive(sqlx::FromRow)]
+struct MarketplaceRow {
+    #[sqlx(flatten)]
+    item: ShopItem,
+    shop_name: String,
+    group_conversation_id: String,
+    group_name: String,
+    group_image_url: Option<String>,
+    price_usd_estimate: Option<f64>,
+    units_sold: i64,
+    average_rating: Option<f64>,
+    review_count: i64,
+    total: i64,
+}
+
+/// Search items across every shop attached to a public group. Token-priced items
+/// are converted at the latest CoinGecko price so they can be filtered and sorted
+/// alongside USD-priced ones; tokens without a price have no estimate.
+pub async fn search_items(pool: &DbPool, query: MarketplaceSearchQuery) -> Result<MarketplaceSearchResponse> {
+    let q = query.q.map(|q| q.trim().to_string()).filter(|q| !q.is_empty());
+    if q.as_ref().is_some_and(|q| q.chars().count() > MAX_QUERY_LEN) {
+        return Err(anyhow!("Search queries can be at most {} characters", MAX_QUERY_LEN));
+    }
+    if query.min_price_usd.is_some_and(|p| p < 0.0) || query.max_price_usd.is_some_and(|p| p < 0.0) {
+        return Err(anyhow!("Price filters can't be negative"));
+    }
+    if let (Some(min), Some(max)) = (query.min_price_usd, query.max_price_usd) {
+        if min > max {
+            return Err(anyhow!("min_price_usd must not exceed max_price_usd"));
+        }
+    }
+    if query.min_rating.is_some_and(|r| !(0.0..=5.0).contains(&r)) {
+        return Err(anyhow!("min_rating must be between 0 and 5"));
+    }
+
+    let sort = query
+        .sort
+        .as_deref()
+        .unwrap_or(if q.is_some() { "relevance" } else { "newest" });
+    let order_by = match sort {
+        "relevance" if q.is_some() => "rank DESC, created_at DESC, id",
+        "relevance" => return Err(anyhow!("Sorting by relevance needs a search query")),
+        "newest" => "created_at DESC, id",
+        "price_asc" => "price_usd_estimate ASC NULLS LAST, id",
+        "price_desc" => "price_usd_estimate DESC NULLS LAST, id",
+        "popularity" => "units_sold DESC, review_count DESC, created_at DESC, id",
+        _ => return Err(anyhow!("sort must be relevance, newest, price_asc, price_desc or popularity")),
+    };
+
+    let token = query.token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let category = shop_service::normalize_category(query.category);
+    let tag = query
+        .tag
+        .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+        .filter(|t| !t.is_empty());
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let token_addresses: Vec<String> = order_service::QUOTE_TOKENS
+        .iter()
+        .map(|t| t.address.unwrap_or_default().to_string())
+        .collect();
+    let token_decimals: Vec<i32> = order_service::QUOTE_TOKENS.iter().map(|t| t.decimals).collect();
+    let coingecko_ids: Vec<&str> = order_service::QUOTE_TOKENS.iter().map(|t| t.coingecko_id).collect();
+
+    // `order_by` only ever holds one of the fixed clauses above
+    let sql = format!(
+        r#"
+        WITH quote_tokens AS (
+            SELECT * FROM UNNEST($1::TEXT[], $2::INT[], $3::TEXT[]) AS qt(address, decimals, coingecko_id)
+        ), ratings AS (
+            SELECT item_id, AVG(rating)::FLOAT8 AS average_rating, COUNT(*) AS review_count
+            FROM shop_item_reviews
+            WHERE NOT is_hidden
+            GROUP BY item_id
+        ), sold AS (
+            SELECT oi.item_id, SUM(oi.quantity)::BIGINT AS units_sold
+            FROM order_items oi
+            JOIN orders o ON o.id = oi.order_id
+            WHERE o.status IN ('paid', 'fulfilled') AND oi.item_id IS NOT NULL
+            GROUP BY oi.item_id
+        ), candidates AS (
+            SELECT si.*,
+                   s.name AS shop_name,
+                   g.conversation_id AS group_conversation_id,
+                   g.name AS group_name,
+                   g.image_url AS group_image_url,
+                   COALESCE(
+                       si.price_usd_cents / 100.0,
+                       CASE WHEN si.price ~ '^[0-9]+$'
+                            THEN si.price::NUMERIC / 10::NUMERIC ^ qt.decimals * tp.price_usd::NUMERIC
+                       END
+                   )::FLOAT8 AS price_usd_estimate,
+                   COALESCE(sold.units_sold, 0) AS units_sold,
+                   ratings.average_rating,
+                   COALESCE(ratings.review_count, 0) AS review_count,
+                   CASE WHEN $4::TEXT IS NULL THEN 0
+                        ELSE ts_rank(si.search_vector, websearch_to_tsquery('english', $4))
+                   END AS rank
+            FROM shop_items si
+            JOIN shops s ON s.id = si.shop_id
+            JOIN public_groups g ON g.conversation_id = s.conversation_id AND g.is_public
+            LEFT JOIN quote_tokens qt ON qt.address = LOWER(COALESCE(si.token_address, ''))
+            LEFT JOIN token_prices tp ON tp.coingecko_id = qt.coingecko_id
+            LEFT JOIN sold ON sold.item_id = si.id
+            LEFT JOIN ratings ON ratings.item_id = si.id
+            WHERE ($4::TEXT IS NULL OR si.search_vector @@ websearch_to_tsquery('english', $4))
+              AND ($5::TEXT IS NULL OR UPPER(si.token_symbol) = UPPER($5))
+              AND ($6::TEXT IS NULL OR si.category = $6)
+              AND ($7::TEXT IS NULL OR $7 = ANY(si.tags))
+              AND (si.sale_ends_at IS NULL OR si.sale_ends_at > NOW())
+        )
+        SELECT *, COUNT(*) OVER () AS total
+        FROM candidates
+        WHERE ($8::FLOAT8 IS NULL OR price_usd_estimate >= $8)
+          AND ($9::FLOAT8 IS NULL OR price_usd_estimate <= $9)
+          AND ($10::FLOAT8 IS NULL OR average_rating >= $10)
+        ORDER BY {}
+        LIMIT $11 OFFSET $12
+        "#,
+        order_by
+    );
+
+    let rows = sqlx::query_as::<_, MarketplaceRow>(&sql)
+        .bind(&token_addresses)
+        .bind(&token_decimals)
+        .bind(&coingecko_ids)
+        .bind(&q)
+        .bind(&token)
+        .bind(&category)
+        .bind(&tag)
+        .bind(query.min_price_usd)
+        .bind(query.max_price_usd)
+        .bind(query.min_rating)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    let total = rows.first().map_or(0, |r| r.total);
+    let items = rows
+        .into_iter()
+        .map(|row| {
+            let shop_id = row.item.shop_id.to_string();
+            let mut item = ItemResponse::from(row.item);
+            item.average_rating = row.average_rating.map(|r| (r * 10.0).round() / 10.0);
+            item.review_count = row.review_count;
+            MarketplaceItemResponse {
+                item,
+                price_usd_estimate: row.price_usd_estimate.map(|p| (p * 100.0).round() / 100.0),
+                units_sold: row.units_sold,
+                shop: MarketplaceShop { id: shop_id, name: row.shop_name },
+                group: MarketplaceGroup {
+                    conversation_id: row.group_conversation_id,
+                    name: row.group_name,
+                    image_url: row.group_image_url,
+                },
+            }
+        })
+        .collect();
+
+    Ok(MarketplaceSearchResponse { items, total, limit, offset })
+}
+
+/// Categories in use by public shops, most items first
+pub async fn get_categories(pool: &DbPool) -> Result<Vec<MarketplaceCategory>> {
+    let categories = sqlx::query_as::<_, MarketplaceCategory>(
+        r#"
+        SELECT si.category, COUNT(*) AS item_count
+        FROM shop_items si
+        JOIN shops s ON s.id = si.shop_id
+        JOIN public_groups g ON g.conversation_id = s.conversation_id AND g.is_public
+        WHERE si.category IS NOT NULL
+          AND (si.sale_ends_at IS NULL OR si.sale_ends_at > NOW())
+        GROUP BY si.category
+        ORDER BY item_count DESC, si.category
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(categories)
+}
@@ -1,10 +1,29 @@
 pub mod payment_service;
 pub mod token_gate_service;
+pub mod basename_service;
+pub mod ens_service;
+pub mod eas_service;
+pub mod log_scanner;
+pub mod gate_pass_service;
 pub mod shop_service;
+pub mod order_service;
+pub mod delivery_service;
+pub mod item_rule_service;
+pub mod promotion_service;
+pub mod analytics_service;
+pub mod review_service;
+pub mod marketplace_service;
 pub mod admin_service;
 pub mod profile_service;
+pub mod reserved_username_service;
+pub mod social_service;
+pub mod image_service;
+pub mod account_service;
 pub mod group_service;
 pub mod alpha_bot_service;
 pub mod event_watcher;
 pub mod feed_service;
 pub mod feed_poller;
+pub mod gate_reverifier;
+pub mod basename_refresher;
+pub mod order_watcher;
@@ -0,0 +1,703 @@
+use crate::{
+    db::DbPool,
+    models::{
+        CreateOrderRequest, Order, OrderItem, OrderItemResponse, OrderResponse,
+        OrderStatus, RefundOrderRequest, Shop, ShopItem, Transaction, TransactionStatus,
+    },
+    services::{
+        feed_service, item_rule_service, payment_service,
+        promotion_service::{self, Discount},
+    },
+};
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Utc};
+use ethers::prelude::{Provider, Http, U256};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+const MAX_ITEMS_PER_ORDER: usize = 50;
+const MAX_QUANTITY: i32 = 1000;
+/// How long a pending order holds its stock before it expires
+const RESERVATION_MINUTES: i64 = 30;
+/// How long past its reservation an order is kept while a payment sent in time
+/// waits for confirmations (a dropped transaction never confirms)
+const CONFIRMATION_GRACE_MINUTES: i64 = 30;
+/// How long a USD-priced order honours its token quote
+const QUOTE_WINDOW_MINUTES: i64 = 10;
+/// Token prices older than this are too stale to quote from
+const MAX_PRICE_AGE_SECS: i64 = 300;
+/// Payments this far below the quoted amount are still accepted (100 bps = 1%)
+const QUOTE_SLIPPAGE_BPS: u64 = 100;
+
+/// A token buyers can pay USD-priced items with
+pub struct QuoteToken {
+    pub symbol: &'static str,
+    pub address: Option<&'static str>, // None for native ETH
+    pub decimals: i32,
+    pub coingecko_id: &'static str,
+}
+
+pub const QUOTE_TOKENS: &[QuoteToken] = &[
+    QuoteToken { symbol: "ETH", address: None, decimals: 18, coingecko_id: "ethereum" },
+    QuoteToken {
+        symbol: "USDC",
+        address: Some("0x833589fcd6edb6e08f4c7c32d4f71b54bda02913"), // Native USDC on Base
+        decimals: 6,
+        coingecko_id: "usd-coin",
+    },
+];
+
+/// A locked conversion of the order's USD total into the payment token
+struct Quote {
+    token: &'static QuoteToken,
+    price_usd: f64,
+}
+
+impl Quote {
+    /// Smallest token units for an amount in cents, rounded up so the seller is never short
+    fn token_amount(&self, cents: i64) -> Result<U256> {
+        let units = (cents as f64 / 100.0) / self.price_usd * 10f64.powi(self.token.decimals);
+        U256::from_dec_str(&format!("{:.0}", units.ceil()))
+            .map_err(|_| anyhow!("Could not convert the USD price to {}", self.token.symbol))
+    }
+}
+
+/// Create a pending order. Item prices, the token and the seller address are
+/// snapshotted so later catalog edits don't change what the buyer owes. Stocked
+/// items are reserved until the order is paid or its reservation expires. Holder
+/// rules are checked against the buyer's wallet: gated items are refused to
+/// wallets that don't qualify and the best discount tier met is applied.
+pub async fn create_order(
+    pool: &DbPool,
+    shop_id: &Uuid,
+    req: CreateOrderRequest,
+) -> Result<OrderResponse> {
+    if req.items.is_empty() {
+        return Err(anyhow!("Order must contain at least one item"));
+    }
+    if req.items.len() > MAX_ITEMS_PER_ORDER {
+        return Err(anyhow!("Order can contain at most {} items", MAX_ITEMS_PER_ORDER));
+    }
+    if req.items.iter().any(|i| i.quantity < 1 || i.quantity > MAX_QUANTITY) {
+        return Err(anyhow!("Quantity must be between 1 and {}", MAX_QUANTITY));
+    }
+    let unique_items: HashSet<Uuid> = req.items.iter().map(|i| i.item_id).collect();
+    if unique_items.len() != req.items.len() {
+        return Err(anyhow!("Each item can only appear once per order"));
+    }
+    req.buyer_address
+        .parse::<ethers::types::Address>()
+        .map_err(|_| anyhow!("Invalid buyer address"))?;
+
+    let shop = sqlx::query_as::<_, Shop>("SELECT * FROM shops WHERE id = $1")
+        .bind(shop_id)
+        .fetch_one(pool)
+        .await?;
+
+    let item_ids: Vec<Uuid> = req.items.iter().map(|i| i.item_id).collect();
+
+    // On-chain checks happen before any rows are locked
+    let eligibility = item_rule_service::evaluate_items(pool, &item_ids, &req.buyer_address).await?;
+
+    let mut tx = pool.begin().await?;
+
+    // Lock the items (in id order, so concurrent checkouts can't deadlock) so stock
+    // and per-wallet counts can't change between the checks and the reservation
+    let catalog = sqlx::query_as::<_, ShopItem>(
+        "SELECT * FROM shop_items WHERE shop_id = $1 AND id = ANY($2) ORDER BY id FOR UPDATE",
+    )
+    .bind(shop_id)
+    .bind(&item_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    // All items must be paid with one token in a single transfer. USD-priced items
+    // are converted to the token the buyer picks, token-priced ones are paid as listed.
+    let now = Utc::now();
+    let mut lines: Vec<(&ShopItem, i32)> = Vec::new();
+    for requested in &req.items {
+        let item = catalog
+            .iter()
+            .find(|i| i.id == requested.item_id)
+            .ok_or_else(|| anyhow!("Item {} not found in this shop", requested.item_id))?;
+
+        if let Some((first, _)) = lines.first() {
+            if item.price_usd_cents.is_some() != first.price_usd_cents.is_some() {
+                return Err(anyhow!("USD-priced and token-priced items must be ordered separately"));
+            }
+            if item.price_usd_cents.is_none() && !same_token(&item.token_address, &first.token_address) {
+                return Err(anyhow!("All items in an order must be priced in the same token"));
+            }
+        }
+        if let Some(e) = eligibility.get(&item.id).filter(|e| !e.allowed) {
+            return Err(anyhow!(
+                "'{}' is only available to wallets that meet '{}'",
+                item.name,
+                e.gate_name.as_deref().unwrap_or("the holder requirements")
+            ));
+        }
+        if item.sale_starts_at.is_some_and(|t| now < t) {
+            return Err(anyhow!("Sale for '{}' has not started yet", item.name));
+        }
+        if item.sale_ends_at.is_some_and(|t| now >= t) {
+            return Err(anyhow!("Sale for '{}' has ended", item.name));
+        }
+        if let Some(stock) = item.stock {
+            if stock < requested.quantity {
+                return Err(if stock == 0 {
+                    anyhow!("'{}' is sold out", item.name)
+                } else {
+                    anyhow!("Only {} of '{}' left", stock, item.name)
+                });
+            }
+        }
+        if let Some(max_per_wallet) = item.max_per_wallet {
+            let already_bought = wallet_quantity(&mut tx, item.id, &req.buyer_address).await?;
+            if already_bought + requested.quantity as i64 > max_per_wallet as i64 {
+                return Err(anyhow!(
+                    "'{}' is limited to {} per wallet ({} already ordered)",
+                    item.name, max_per_wallet, already_bought
+                ));
+            }
+        }
+
+        lines.push((item, requested.quantity));
+    }
+    let first = lines[0].0;
+
+    let quote = if first.price_usd_cents.is_some() {
+        Some(lock_quote(pool, req.pay_with.as_deref().unwrap_or(&first.token_symbol)).await?)
+    } else {
+        if let Some(pay_with) = &req.pay_with {
+            if !pay_with.eq_ignore_ascii_case(&first.token_symbol) {
+                return Err(anyhow!("These items can only be paid in {}", first.token_symbol));
+            }
+        }
+        None
+    };
+
+    // Per-unit price in the payment token, snapshotted on each line
+    let mut total = U256::zero();
+    let mut usd_total: i64 = 0;
+    let mut priced_lines = Vec::with_capacity(lines.len());
+    for (item, quantity) in lines {
+        let applied = eligibility.get(&item.id).and_then(|e| e.applied.as_ref());
+        let discount_bps = applied.map_or(0, |r| r.discount_bps);
+
+        let (price, price_usd_cents) = match (&quote, item.price_usd_cents) {
+            (Some(quote), Some(cents)) => {
+                let cents = cents - cents * discount_bps as i64 / 10_000;
+                usd_total = usd_total
+                    .checked_add(cents.saturating_mul(quantity as i64))
+                    .ok_or_else(|| anyhow!("Order total is too large"))?;
+                (quote.token_amount(cents)?, Some(cents))
+            }
+            _ => {
+                let listed = U256::from_dec_str(&item.price)
+                    .map_err(|_| anyhow!("Item '{}' has a price that can't be paid on-chain", item.name))?;
+                (listed - listed * U256::from(discount_bps) / U256::from(10_000u64), None)
+            }
+        };
+        total = price
+            .checked_mul(U256::from(quantity))
+            .and_then(|line| total.checked_add(line))
+            .ok_or_else(|| anyhow!("Order total is too large"))?;
+        priced_lines.push((item, quantity, price, price_usd_cents, applied));
+    }
+
+    let (token_address, token_symbol) = match &quote {
+        Some(quote) => (quote.token.address.map(str::to_string), quote.token.symbol.to_string()),
+        None => (first.token_address.as_ref().map(|a| a.to_lowercase()), first.token_symbol.clone()),
+    };
+
+    // Promo codes come off the subtotal of the items they apply to, after holder discounts
+    let mut promotion = None;
+    let mut discount_amount = U256::zero();
+    let mut discount_usd_cents = None;
+    if let Some(code) = req.promo_code.as_deref().filter(|c| !c.trim().is_empty()) {
+        let promo = promotion_service::lock_for_checkout(&mut tx, shop_id, code, &req.buyer_address).await?;
+
+        let eligible: Vec<_> = priced_lines
+            .iter()
+            .filter(|(item, ..)| promotion_service::applies_to(&promo, &item.id))
+            .collect();
+        if eligible.is_empty() {
+            return Err(anyhow!("Promo code '{}' doesn't apply to any item in this order", promo.code));
+        }
+        let eligible_amount = eligible
+            .iter()
+            .fold(U256::zero(), |sum, (_, quantity, price, ..)| sum + *price * U256::from(*quantity));
+        let eligible_usd_cents = quote.as_ref().map(|_| {
+            eligible
+                .iter()
+                .map(|(_, quantity, _, cents, ..)| cents.unwrap_or(0) * *quantity as i64)
+                .sum::<i64>()
+        });
+
+        match promotion_service::compute_discount(&promo, eligible_usd_cents, eligible_amount, token_address.as_deref())? {
+            Discount::UsdCents(cents) => {
+                // Same conversion as the lines, capped so rounding can't exceed the total
+                let quote = quote.as_ref().ok_or_else(|| anyhow!("Order has no USD quote"))?;
+                discount_amount = quote.token_amount(cents)?.min(total);
+                usd_total -= cents;
+                discount_usd_cents = Some(cents);
+            }
+            Discount::Token(amount) => discount_amount = amount.min(total),
+        }
+        total -= discount_amount;
+        promotion = Some(promo);
+    }
+    let min_accepted = quote.as_ref().map(|_| {
+        (total * U256::from(10_000 - QUOTE_SLIPPAGE_BPS) / U256::from(10_000u64)).to_string()
+    });
+    // Nothing to pay after a full discount: the order is paid as soon as it's placed
+    let (status, paid_at) = if total.is_zero() {
+        (OrderStatus::Paid, Some(now))
+    } else {
+        (OrderStatus::Pending, None)
+    };
+
+    let order = sqlx::query_as::<_, Order>(
+        r#"
+        INSERT INTO orders (shop_id, conversation_id, buyer_address, seller_address,
+                            token_address, token_symbol, total_amount, reserved_until,
+                            quote_usd_cents, quote_token_price_usd, quote_expires_at, min_accepted_amount,
+                            promotion_id, promo_code, discount_amount, discount_usd_cents, status, paid_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+        RETURNING *
+        "#,
+    )
+    .bind(shop_id)
+    .bind(&shop.conversation_id)
+    .bind(req.buyer_address.to_lowercase())
+    .bind(shop.owner_address.to_lowercase())
+    .bind(token_address)
+    .bind(token_symbol)
+    .bind(total.to_string())
+    .bind(now + Duration::minutes(RESERVATION_MINUTES))
+    .bind(quote.as_ref().map(|_| usd_total))
+    .bind(quote.as_ref().map(|q| q.price_usd))
+    .bind(quote.as_ref().map(|_| now + Duration::minutes(QUOTE_WINDOW_MINUTES)))
+    .bind(min_accepted)
+    .bind(promotion.as_ref().map(|p| p.id))
+    .bind(promotion.as_ref().map(|p| p.code.as_str()))
+    .bind(promotion.as_ref().map(|_| discount_amount.to_string()))
+    .bind(discount_usd_cents)
+    .bind(status)
+    .bind(paid_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if let Some(promo) = &promotion {
+        promotion_service::record_redemption(
+            &mut tx,
+            &promo.id,
+            &order.id,
+            &req.buyer_address,
+            discount_amount,
+            discount_usd_cents,
+        )
+        .await?;
+    }
+
+    let mut items = Vec::new();
+    for (item, quantity, price, price_usd_cents, applied) in priced_lines {
+        // The row is locked, but keep the guard in SQL so stock can never go negative
+        let reserved = item.stock.is_some();
+        if reserved {
+            let updated = sqlx::query("UPDATE shop_items SET stock = stock - $2 WHERE id = $1 AND stock >= $2")
+                .bind(item.id)
+                .bind(quantity)
+                .execute(&mut *tx)
+                .await?;
+            if updated.rows_affected() == 0 {
+                return Err(anyhow!("'{}' is sold out", item.name));
+            }
+        }
+
+        let order_item = sqlx::query_as::<_, OrderItem>(
+            r#"
+            INSERT INTO order_items (order_id, item_id, name, price, quantity, reserved, price_usd_cents,
+                                     rule_id, rule_name, discount_bps)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+            "#,
+        )
+        .bind(order.id)
+        .bind(item.id)
+        .bind(&item.name)
+        .bind(price.to_string())
+        .bind(quantity)
+        .bind(reserved)
+        .bind(price_usd_cents)
+        .bind(applied.map(|r| r.rule_id))
+        .bind(applied.map(|r| r.name.as_str()))
+        .bind(applied.map_or(0, |r| r.discount_bps))
+        .fetch_one(&mut *tx)
+        .await?;
+        items.push(OrderItemResponse::from(order_item));
+    }
+
+    tx.commit().await?;
+
+    let mut response = OrderResponse::from(order);
+    response.items = items;
+    Ok(response)
+}
+
+/// Quote USD prices in the buyer's chosen token at the poller's latest price
+async fn lock_quote(pool: &DbPool, pay_with: &str) -> Result<Quote> {
+    let token = QUOTE_TOKENS
+        .iter()
+        .find(|t| t.symbol.eq_ignore_ascii_case(pay_with))
+        .ok_or_else(|| {
+            let symbols: Vec<&str> = QUOTE_TOKENS.iter().map(|t| t.symbol).collect();
+            anyhow!("USD-priced items can be paid in {}", symbols.join(" or "))
+        })?;
+
+    let price = feed_service::get_token_price(pool, token.coingecko_id)
+        .await?
+        .filter(|p| Utc::now() - p.fetched_at <= Duration::seconds(MAX_PRICE_AGE_SECS))
+        .ok_or_else(|| anyhow!("No recent {} price available, try again shortly", token.symbol))?;
+
+    Ok(Quote { token, price_usd: price.price_usd })
+}
+
+/// Units of an item a wallet already has in pending, paid or fulfilled orders
+async fn wallet_quantity(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    item_id: Uuid,
+    buyer_address: &str,
+) -> Result<i64> {
+    let (quantity,): (i64,) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(SUM(oi.quantity), 0)::BIGINT
+        FROM order_items oi
+        JOIN orders o ON o.id = oi.order_id
+        WHERE oi.item_id = $1
+          AND LOWER(o.buyer_address) = LOWER($2)
+          AND o.status IN ('pending', 'paid', 'fulfilled')
+        "#,
+    )
+    .bind(item_id)
+    .bind(buyer_address)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(quantity)
+}
+
+fn same_token(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        _ => false,
+    }
+}
+
+pub async fn get_order(pool: &DbPool, order_id: &Uuid) -> Result<OrderResponse> {
+    let order = sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_one(pool)
+        .await?;
+
+    with_items(pool, vec![order]).await.map(|mut o| o.remove(0))
+}
+
+pub async fn get_buyer_orders(pool: &DbPool, buyer_address: &str) -> Result<Vec<OrderResponse>> {
+    let orders = sqlx::query_as::<_, Order>(
+        "SELECT * FROM orders WHERE LOWER(buyer_address) = LOWER($1) ORDER BY created_at DESC",
+    )
+    .bind(buyer_address)
+    .fetch_all(pool)
+    .await?;
+
+    with_items(pool, orders).await
+}
+
+pub async fn get_seller_orders(pool: &DbPool, seller_address: &str) -> Result<Vec<OrderResponse>> {
+    let orders = sqlx::query_as::<_, Order>(
+        "SELECT * FROM orders WHERE LOWER(seller_address) = LOWER($1) ORDER BY created_at DESC",
+    )
+    .bind(seller_address)
+    .fetch_all(pool)
+    .await?;
+
+    with_items(pool, orders).await
+}
+
+/// Orders placed in a shop, for its owner only
+pub async fn get_shop_orders(pool: &DbPool, shop_id: &Uuid, seller_address: &str) -> Result<Vec<OrderResponse>> {
+    let (owner_address,): (String,) = sqlx::query_as("SELECT owner_address FROM shops WHERE id = $1")
+        .bind(shop_id)
+        .fetch_one(pool)
+        .await?;
+    if !owner_address.eq_ignore_ascii_case(seller_address) {
+        return Err(anyhow!("Only the shop owner can see its orders"));
+    }
+
+    let orders = sqlx::query_as::<_, Order>(
+        "SELECT * FROM orders WHERE shop_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(shop_id)
+    .fetch_all(pool)
+    .await?;
+
+    with_items(pool, orders).await
+}
+
+/// Attach line items to orders with one query
+async fn with_items(pool: &DbPool, orders: Vec<Order>) -> Result<Vec<OrderResponse>> {
+    let order_ids: Vec<Uuid> = orders.iter().map(|o| o.id).collect();
+    let items = sqlx::query_as::<_, OrderItem>(
+        "SELECT * FROM order_items WHERE order_id = ANY($1) ORDER BY created_at ASC",
+    )
+    .bind(&order_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(orders
+        .into_iter()
+        .map(|order| {
+            let order_id = order.id;
+            let mut response = OrderResponse::from(order);
+            response.items = items
+                .iter()
+                .filter(|i| i.order_id == order_id)
+                .map(|i| OrderItemResponse {
+                    item_id: i.item_id.map(|id| id.to_string()),
+                    name: i.name.clone(),
+                    price: i.price.clone(),
+                    price_usd: i.price_usd_cents.map(|c| c as f64 / 100.0),
+                    quantity: i.quantity,
+                    rule_name: i.rule_name.clone(),
+                    discount_bps: i.discount_bps,
+                })
+                .collect();
+            response
+        })
+        .collect())
+}
+
+pub async fn fulfill_order(pool: &DbPool, order_id: &Uuid, seller_address: &str) -> Result<OrderResponse> {
+    let order = load_for_seller(pool, order_id, seller_address).await?;
+    if order.status != OrderStatus::Paid {
+        return Err(anyhow!("Only paid orders can be fulfilled"));
+    }
+
+    sqlx::query(
+        "UPDATE orders SET status = 'fulfilled', fulfilled_at = NOW() WHERE id = $1 AND status = 'paid'",
+    )
+    .bind(order_id)
+    .execute(pool)
+    .await?;
+
+    get_order(pool, order_id).await
+}
+
+pub async fn refund_order(
+    pool: &DbPool,
+    order_id: &Uuid,
+    seller_address: &str,
+    req: RefundOrderRequest,
+) -> Result<OrderResponse> {
+    let order = load_for_seller(pool, order_id, seller_address).await?;
+    if !matches!(order.status, OrderStatus::Paid | OrderStatus::Fulfilled) {
+        return Err(anyhow!("Only paid or fulfilled orders can be refunded"));
+    }
+    req.refund_tx_hash
+        .parse::<ethers::types::H256>()
+        .map_err(|_| anyhow!("Invalid refund transaction hash"))?;
+
+    sqlx::query(
+        r#"
+        UPDATE orders SET status = 'refunded', refund_tx_hash = $2, refunded_at = NOW()
+        WHERE id = $1 AND status IN ('paid', 'fulfilled')
+        "#,
+    )
+    .bind(order_id)
+    .bind(&req.refund_tx_hash)
+    .execute(pool)
+    .await?;
+
+    get_order(pool, order_id).await
+}
+
+/// Load an order for `seller_address`, the signed-in wallet, which must be its seller
+async fn load_for_seller(pool: &DbPool, order_id: &Uuid, seller_address: &str) -> Result<Order> {
+    let order = sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_one(pool)
+        .await?;
+
+    if !order.seller_address.eq_ignore_ascii_case(seller_address) {
+        return Err(anyhow!("Only the seller can update this order"));
+    }
+    Ok(order)
+}
+
+// ── Payment matching ──
+
+/// Expire pending orders past their reservation and put their reserved units back
+/// in stock. Both happen in one statement, so an order is released exactly once.
+/// Orders with a matching payment recorded before the deadline that is still waiting
+/// for confirmations are kept (up to CONFIRMATION_GRACE_MINUTES) so check_payment
+/// can mark them paid; this mirrors the match in check_payment.
+pub async fn expire_reservations(pool: &DbPool) -> Result<u64> {
+    let expired: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        WITH expired AS (
+            UPDATE orders o SET status = 'expired'
+            WHERE o.status = 'pending' AND o.reserved_until < NOW()
+              AND NOT (
+                  o.reserved_until > NOW() - make_interval(mins => $1)
+                  AND EXISTS (
+                      SELECT 1 FROM transactions t
+                      WHERE LOWER(t.from_address) = LOWER(o.buyer_address)
+                        AND LOWER(t.to_address) = LOWER(o.seller_address)
+                        AND LOWER(COALESCE(t.token_address, '')) = LOWER(COALESCE(o.token_address, ''))
+                        AND (CASE WHEN t.amount ~ '^[0-9]+$' THEN t.amount::NUMERIC END)
+                            >= COALESCE(o.min_accepted_amount, o.total_amount)::NUMERIC
+                        AND t.status = 'pending'
+                        AND t.created_at >= o.created_at
+                        AND t.created_at <= LEAST(o.quote_expires_at, o.reserved_until) -- LEAST skips NULLs
+                        AND NOT EXISTS (SELECT 1 FROM orders p WHERE p.tx_hash = t.tx_hash)
+                  )
+              )
+            RETURNING o.id
+        ), released AS (
+            SELECT oi.item_id, SUM(oi.quantity)::INTEGER AS quantity
+            FROM order_items oi
+            JOIN expired e ON e.id = oi.order_id
+            WHERE oi.reserved AND oi.item_id IS NOT NULL
+            GROUP BY oi.item_id
+        ), restocked AS (
+            UPDATE shop_items si SET stock = si.stock + r.quantity
+            FROM released r
+            WHERE si.id = r.item_id AND si.stock IS NOT NULL
+        )
+        SELECT id FROM expired
+        "#,
+    )
+    .bind(CONFIRMATION_GRACE_MINUTES as i32)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(expired.len() as u64)
+}
+
+pub async fn get_pending_orders(pool: &DbPool, limit: i64) -> Result<Vec<Order>> {
+    let orders = sqlx::query_as::<_, Order>(
+        "SELECT * FROM orders WHERE status = 'pending' ORDER BY created_at ASC LIMIT $1",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(orders)
+}
+
+/// Look for a transaction that pays this order: buyer → seller, same token, at least
+/// the order total, mined after the order was created and not used by another
+/// order. USD-quoted orders accept the quote less the slippage tolerance, but only
+/// for transactions recorded before the quote expired. Pending candidates are
+/// confirmed on-chain first. Returns true when this call marked the order paid.
+pub async fn check_payment(
+    pool: &DbPool,
+    provider: &Provider<Http>,
+    order: &Order,
+) -> Result<bool> {
+    if order.status != OrderStatus::Pending {
+        return Ok(false);
+    }
+
+    let candidates = sqlx::query_as::<_, Transaction>(
+        r#"
+        SELECT t.* FROM transactions t
+        WHERE LOWER(t.from_address) = LOWER($1)
+          AND LOWER(t.to_address) = LOWER($2)
+          AND LOWER(COALESCE(t.token_address, '')) = LOWER(COALESCE($3, ''))
+          AND (CASE WHEN t.amount ~ '^[0-9]+$' THEN t.amount::NUMERIC END) >= $4::NUMERIC
+          AND t.status <> 'failed'
+          AND t.created_at >= $5
+          AND ($6::TIMESTAMPTZ IS NULL OR t.created_at <= $6)
+          AND NOT EXISTS (SELECT 1 FROM orders o WHERE o.tx_hash = t.tx_hash)
+        ORDER BY t.created_at ASC
+        "#,
+    )
+    .bind(&order.buyer_address)
+    .bind(&order.seller_address)
+    .bind(&order.token_address)
+    .bind(order.min_accepted_amount.as_ref().unwrap_or(&order.total_amount))
+    .bind(order.created_at)
+    .bind(order.quote_expires_at)
+    .fetch_all(pool)
+    .await?;
+
+    for candidate in candidates {
+        let confirmed = match candidate.status {
+            TransactionStatus::Confirmed => candidate,
+            _ => match payment_service::confirm_transaction(pool, provider, &candidate).await? {
+                Some(tx) if matches!(tx.status, TransactionStatus::Confirmed) => tx,
+                _ => continue,
+            },
+        };
+        // A transfer made before checkout can't pay this order, however late it was submitted
+        match payment_service::mined_at(provider, &confirmed).await? {
+            Some(mined_at) if mined_at.timestamp() >= order.created_at.timestamp() => {}
+            _ => continue,
+        }
+
+        let result = sqlx::query(
+            r#"
+            UPDATE orders SET status = 'paid', tx_hash = $2, paid_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(order.id)
+        .bind(&confirmed.tx_hash)
+        .execute(pool)
+        .await;
+
+        match result {
+            Ok(r) => return Ok(r.rows_affected() > 0),
+            // Another order claimed this transaction first
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(false)
+}
+
+/// Run the payment check for one order right away instead of waiting for the watcher
+pub async fn refresh_payment(pool: &DbPool, order_id: &Uuid) -> Result<OrderResponse> {
+    let order = sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_one(pool)
+        .await?;
+
+    let rpc_url = std::env::var("BASE_RPC_URL")
+        .unwrap_or_else(|_| "https://mainnet.base.org".to_string());
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    check_payment(pool, &provider, &order).await?;
+
+    get_order(pool, order_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_token_amount() {
+        let usdc = Quote { token: &QUOTE_TOKENS[1], price_usd: 1.0 };
+        assert_eq!(usdc.token_amount(2000).unwrap(), U256::from(20_000_000u64));
+
+        // $20 at $3000/ETH rounds up to the next wei
+        let eth = Quote { token: &QUOTE_TOKENS[0], price_usd: 3000.0 };
+        let wei = eth.token_amount(2000).unwrap();
+        assert!(wei >= U256::from(6_666_666_666_666_666u64));
+        assert!(wei <= U256::from(6_666_666_666_666_700u64));
+    }
+}
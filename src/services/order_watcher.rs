@@ -0,0 +1,50 @@
+use ethers::prelude::*;
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::services::order_service;
+
+const LOOP_SLEEP_SECS: u64 = 30;
+const BATCH_SIZE: i64 = 200; // pending orders checked per pass
+
+/// Spawn the order payment watcher background task. Call once from main.rs.
+pub fn spawn(pool: PgPool, rpc_url: String) {
+    tokio::spawn(async move {
+        log::info!("🛒 Order payment watcher starting...");
+        let provider = match Provider::<Http>::try_from(rpc_url.as_str()) {
+            Ok(p) => p,
+            Err(e) => {
+                log::error!("Order payment watcher fatal error: {}", e);
+                return;
+            }
+        };
+        run_loop(pool, provider).await;
+    });
+}
+
+async fn run_loop(pool: PgPool, provider: Provider<Http>) {
+    loop {
+        match order_service::get_pending_orders(&pool, BATCH_SIZE).await {
+            Ok(orders) => {
+                for order in &orders {
+                    match order_service::check_payment(&pool, &provider, order).await {
+                        Ok(true) => log::info!("🛒 Order {} paid", order.id),
+                        Ok(false) => {}
+                        Err(e) => log::warn!("Order watcher: payment check for {} failed: {}", order.id, e),
+                    }
+                }
+            }
+            Err(e) => log::error!("Order watcher: failed to load pending orders: {}", e),
+        }
+
+        // After the payment pass, so an order paid just before its deadline isn't expired.
+        // Payments sent in time but still confirming hold their order until they confirm.
+        match order_service::expire_reservations(&pool).await {
+            Ok(0) => {}
+            Ok(n) => log::info!("🛒 Expired {} unpaid order(s) and released their stock", n),
+            Err(e) => log::error!("Order watcher: failed to expire reservations: {}", e),
+        }
+
+        tokio::time::sleep(Duration::from_secs(LOOP_SLEEP_SECS)).await;
+    }
+}
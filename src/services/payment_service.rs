@@ -2,13 +2,28 @@ use crate::{
     db::DbPool,
     models::{CreateTransactionRequest, Transaction, TransactionStatus},
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use ethers::prelude::*;
+use std::str::FromStr;
 use uuid::Uuid;
 
+/// Blocks on top of the payment block before a transfer counts as confirmed
+const MIN_CONFIRMATIONS: u64 = 3;
+
+/// The one stored spelling of a transaction hash: lowercase with a 0x prefix. The
+/// chain accepts any case with or without the prefix, so without this one transfer
+/// could be recorded under several hashes and pay several orders.
+pub fn canonical_tx_hash(tx_hash: &str) -> Result<String> {
+    let hash = H256::from_str(tx_hash).map_err(|_| anyhow!("Invalid transaction hash"))?;
+    Ok(format!("{:#x}", hash))
+}
+
 pub async fn create_transaction(
     pool: &DbPool,
     req: CreateTransactionRequest,
 ) -> Result<Transaction> {
+    let tx_hash = canonical_tx_hash(&req.tx_hash)?;
     let tx = sqlx::query_as::<_, Transaction>(
         r#"
         INSERT INTO transactions (
@@ -21,7 +36,7 @@ pub async fn create_transaction(
         "#,
     )
     .bind(Uuid::new_v4())
-    .bind(&req.tx_hash)
+    .bind(&tx_hash)
     .bind(&req.from_address)
     .bind(&req.to_address)
     .bind(&req.amount)
@@ -40,10 +55,14 @@ pub async fn get_transaction_by_hash(
     pool: &DbPool,
     tx_hash: &str,
 ) -> Result<Option<Transaction>> {
+    let tx_hash = match canonical_tx_hash(tx_hash) {
+        Ok(hash) => hash,
+        Err(_) => return Ok(None),
+    };
     let tx = sqlx::query_as::<_, Transaction>(
         "SELECT * FROM transactions WHERE tx_hash = $1"
     )
-    .bind(tx_hash)
+    .bind(&tx_hash)
     .fetch_optional(pool)
     .await?;
 
@@ -86,3 +105,105 @@ pub async fn update_transaction_status(
 
     Ok(tx)
 }
+
+/// Check a pending transaction against the chain and record the result.
+///
+/// The transfer must be mined successfully with MIN_CONFIRMATIONS and move at
+/// least the recorded amount from `from_address` to `to_address` (native value, or
+/// an ERC-20 Transfer log from the recorded token). A mined transaction that doesn't
+/// match is marked failed. Returns None while it is still unmined or too recent.
+pub async fn confirm_transaction(
+    pool: &DbPool,
+    provider: &Provider<Http>,
+    tx: &Transaction,
+) -> Result<Option<Transaction>> {
+    let hash: H256 = tx.tx_hash.parse()?;
+
+    let receipt = match provider.get_transaction_receipt(hash).await? {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+    let block_number = match receipt.block_number {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+
+    let current_block = provider.get_block_number().await?;
+    if current_block.saturating_sub(block_number) < U64::from(MIN_CONFIRMATIONS) {
+        return Ok(None);
+    }
+
+    let succeeded = receipt.status == Some(U64::from(1));
+    let matches = succeeded && transfer_matches(provider, tx, &receipt).await?;
+    let status = if matches {
+        TransactionStatus::Confirmed
+    } else {
+        TransactionStatus::Failed
+    };
+
+    let updated =
+        update_transaction_status(pool, &tx.tx_hash, status, Some(block_number.as_u64() as i64)).await?;
+    Ok(Some(updated))
+}
+
+/// Chain time of the block that mined a confirmed transaction. `created_at` is only
+/// when the hash was submitted to us, which can be long after the transfer.
+pub async fn mined_at(provider: &Provider<Http>, tx: &Transaction) -> Result<Option<DateTime<Utc>>> {
+    let block_number = match tx.block_number {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+    let block = match provider.get_block(block_number as u64).await? {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+    Ok(DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0))
+}
+
+async fn transfer_matches(
+    provider: &Provider<Http>,
+    tx: &Transaction,
+    receipt: &TransactionReceipt,
+) -> Result<bool> {
+    let from: Address = tx.from_address.parse()?;
+    let to: Address = tx.to_address.parse()?;
+    let amount = U256::from_dec_str(&tx.amount)?;
+
+    match &tx.token_address {
+        None => {
+            let onchain = match provider.get_transaction(receipt.transaction_hash).await? {
+                Some(t) => t,
+                None => return Ok(false),
+            };
+            Ok(onchain.from == from && onchain.to == Some(to) && onchain.value >= amount)
+        }
+        Some(token_address) => {
+            let token: Address = token_address.parse()?;
+            let transfer_topic = H256::from(ethers::utils::keccak256("Transfer(address,address,uint256)"));
+            Ok(receipt.logs.iter().any(|log| {
+                log.address == token
+                    && log.topics.len() == 3
+                    && log.topics[0] == transfer_topic
+                    && log.topics[1] == H256::from(from)
+                    && log.topics[2] == H256::from(to)
+                    && U256::from_big_endian(&log.data) >= amount
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_tx_hash() {
+        let canonical = "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060";
+        assert_eq!(canonical_tx_hash(canonical).unwrap(), canonical);
+        assert_eq!(canonical_tx_hash(&canonical.to_uppercase().replace("0X", "0x")).unwrap(), canonical);
+        assert_eq!(canonical_tx_hash(&canonical[2..]).unwrap(), canonical);
+
+        assert!(canonical_tx_hash("0x5c504ed4").is_err());
+        assert!(canonical_tx_hash("not a hash").is_err());
+    }
+}
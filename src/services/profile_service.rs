@@ -1,10 +1,19 @@
 use crate::db::DbPool;
-use crate::models::{CreateProfileRequest, UpdateProfileRequest, UserProfile, SearchResult};
+use crate::models::{
+    CreateProfileRequest, PrivacySettings, SearchHighlight, UpdatePrivacyRequest, UpdateProfileRequest, UserProfile,
+    SearchResult,
+};
+use crate::services::social_service::{self, Relations};
+use crate::services::{basename_service, ens_service, image_service, reserved_username_service, token_gate_service};
 use anyhow::{anyhow, Result};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use ethers::prelude::{Address, Http, Provider};
 use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
 
 const USERNAME_CHANGE_COOLDOWN_DAYS: i64 = 30;
+const USERNAME_REDIRECT_DAYS: i64 = 30; // Old names redirect and stay locked this long
+const POPULARITY_WEIGHT: f32 = 0.05; // Search rank boost per ln(1 + followers)
 
 /// Validate username format
 /// Rules: 3-30 characters, alphanumeric + underscore, no spaces
@@ -58,7 +67,13 @@ pub async fn get_or_create_profile(
     
     // Try to get existing profile
     if let Ok(profile) = get_profile_by_wallet(pool, &wallet_lower).await {
-        return Ok(profile);
+        // Profiles created before basename discovery get one reverse lookup
+        let profile = if profile.basename.is_none() && profile.basename_checked_at.is_none() {
+            discover_basename(pool, profile).await
+        } else {
+            profile
+        };
+        return Ok(refresh_ens_if_stale(pool, profile).await);
     }
     
     // Create new profile
@@ -75,9 +90,156 @@ pub async fn get_or_create_profile(
     .fetch_one(pool)
     .await?;
     
+    let profile = discover_basename(pool, profile).await;
+    Ok(refresh_ens_if_stale(pool, profile).await)
+}
+
+/// Pick up the wallet's primary Basename via reverse resolution. RPC failures are
+/// only logged so profile init never depends on the chain.
+async fn discover_basename(pool: &DbPool, profile: UserProfile) -> UserProfile {
+    let result = match token_gate_service::base_provider() {
+        Ok(provider) => refresh_basename(pool, &provider, &profile).await,
+        Err(e) => Err(anyhow!("{}", e)),
+    };
+    match result {
+        Ok(updated) => updated,
+        Err(e) => {
+            log::warn!("Basename discovery failed for {}: {}", profile.wallet_address, e);
+            profile
+        }
+    }
+}
+
+/// Re-check a profile's basename on-chain. A name that still resolves to the wallet
+/// is kept; otherwise it is replaced by the wallet's verified primary name, if any.
+pub async fn refresh_basename(
+    pool: &DbPool,
+    provider: &Provider<Http>,
+    profile: &UserProfile,
+) -> Result<UserProfile> {
+    let wallet: Address = profile.wallet_address.parse()?;
+
+    if let Some(basename) = &profile.basename {
+        let owned = basename_service::basename_owned_by(provider, basename, wallet, None)
+            .await
+            .map_err(|e| anyhow!("{}", e))?;
+        if owned {
+            return store_basename(pool, &profile.wallet_address, Some(basename)).await;
+        }
+    }
+
+    let primary = basename_service::primary_basename(provider, wallet)
+        .await
+        .map_err(|e| anyhow!("{}", e))?;
+    store_basename(pool, &profile.wallet_address, primary.as_deref()).await
+}
+
+/// Save an on-chain verified basename (or clear it). The chain is the source of
+/// truth, so a stale claim of the same name on another profile is released.
+async fn store_basename(pool: &DbPool, wallet_address: &str, basename: Option<&str>) -> Result<UserProfile> {
+    let wallet_lower = wallet_address.to_lowercase();
+    let mut tx = pool.begin().await?;
+
+    if let Some(basename) = basename {
+        sqlx::query(
+            r#"
+            UPDATE user_profiles
+            SET basename = NULL, basename_verified_at = NULL
+            WHERE LOWER(basename) = LOWER($1) AND wallet_address != $2
+            "#
+        )
+        .bind(basename)
+        .bind(&wallet_lower)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let profile = sqlx::query_as::<_, UserProfile>(
+        r#"
+        UPDATE user_profiles
+        SET basename = $1,
+            basename_verified_at = CASE WHEN $1 IS NULL THEN NULL ELSE NOW() END,
+            basename_checked_at = NOW()
+        WHERE wallet_address = $2
+        RETURNING *
+        "#
+    )
+    .bind(basename)
+    .bind(&wallet_lower)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
     Ok(profile)
 }
 
+/// Re-resolve the wallet's ENS name and text records once the cached copy is older
+/// than the TTL. Lookup failures keep the cached data and are retried next time.
+async fn refresh_ens_if_stale(pool: &DbPool, profile: UserProfile) -> UserProfile {
+    if profile
+        .ens_resolved_at
+        .is_some_and(|at| Utc::now() - at < ens_service::cache_ttl())
+    {
+        return profile;
+    }
+    match refresh_ens(pool, &profile).await {
+        Ok(updated) => updated,
+        Err(e) => {
+            log::warn!("ENS lookup failed for {}: {}", profile.wallet_address, e);
+            profile
+        }
+    }
+}
+
+pub async fn refresh_ens(pool: &DbPool, profile: &UserProfile) -> Result<UserProfile> {
+    let wallet: Address = profile.wallet_address.parse()?;
+    let provider = ens_service::mainnet_provider()?;
+    let records = ens_service::resolve_records(&provider, wallet).await?;
+
+    let updated = sqlx::query_as::<_, UserProfile>(
+        r#"
+        UPDATE user_profiles
+        SET ens_name = $1, ens_avatar = $2, ens_url = $3, ens_twitter = $4, ens_github = $5,
+            ens_resolved_at = NOW()
+        WHERE wallet_address = $6
+        RETURNING *
+        "#
+    )
+    .bind(&records.name)
+    .bind(&records.avatar)
+    .bind(&records.url)
+    .bind(&records.twitter)
+    .bind(&records.github)
+    .bind(&profile.wallet_address)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(updated)
+}
+
+/// Profiles with a basename that hasn't been checked on-chain within `interval_secs`
+pub async fn get_profiles_due_for_basename_check(
+    pool: &DbPool,
+    interval_secs: i64,
+    limit: i64,
+) -> Result<Vec<UserProfile>> {
+    let profiles = sqlx::query_as::<_, UserProfile>(
+        r#"
+        SELECT * FROM user_profiles
+        WHERE basename IS NOT NULL
+          AND (basename_checked_at IS NULL OR basename_checked_at < NOW() - make_interval(secs => $1))
+        ORDER BY basename_checked_at NULLS FIRST
+        LIMIT $2
+        "#
+    )
+    .bind(interval_secs as f64)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(profiles)
+}
+
 /// Get profile by wallet address
 pub async fn get_profile_by_wallet(pool: &DbPool, wallet_address: &str) -> Result<UserProfile> {
     let profile = sqlx::query_as::<_, UserProfile>(
@@ -102,6 +264,33 @@ pub async fn get_profile_by_username(pool: &DbPool, username: &str) -> Result<Us
     Ok(profile)
 }
 
+/// Get profile by username, following a recent rename: an old name still inside its
+/// grace period resolves to the renamed profile, returned with its new name
+pub async fn resolve_username(pool: &DbPool, username: &str) -> Result<(UserProfile, Option<String>)> {
+    match get_profile_by_username(pool, username).await {
+        Ok(profile) => return Ok((profile, None)),
+        Err(e) if !matches!(e.downcast_ref::<sqlx::Error>(), Some(sqlx::Error::RowNotFound)) => return Err(e),
+        Err(_) => {}
+    }
+
+    let profile = sqlx::query_as::<_, UserProfile>(
+        r#"
+        SELECT p.* FROM username_history h
+        JOIN user_profiles p ON p.wallet_address = h.wallet_address
+        WHERE LOWER(h.old_username) = LOWER($1) AND h.locked_until > NOW()
+          AND p.username IS NOT NULL
+        ORDER BY h.changed_at DESC
+        LIMIT 1
+        "#
+    )
+    .bind(username)
+    .fetch_one(pool)
+    .await?;
+
+    let renamed_to = profile.username.clone();
+    Ok((profile, renamed_to))
+}
+
 /// Get profile by inbox_id
 pub async fn get_profile_by_inbox_id(pool: &DbPool, inbox_id: &str) -> Result<UserProfile> {
     let profile = sqlx::query_as::<_, UserProfile>(
@@ -114,12 +303,44 @@ pub async fn get_profile_by_inbox_id(pool: &DbPool, inbox_id: &str) -> Result<Us
     Ok(profile)
 }
 
-/// Check if username is available
-pub async fn is_username_available(pool: &DbPool, username: &str, current_wallet: &str) -> Result<bool> {
+/// Check if username is available. None when it is, otherwise why `current_wallet`
+/// can't take it: it is blocked or reserved for someone else, or another profile
+/// holds it or a lookalike (same confusable skeleton)
+pub async fn username_unavailable_reason(
+    pool: &DbPool,
+    username: &str,
+    current_wallet: &str,
+) -> Result<Option<String>> {
+    if let Some(reason) = reserved_username_service::blocking_reason(pool, username, current_wallet).await? {
+        return Ok(Some(reason));
+    }
+
+    // Names given up in a rename are held for their previous owner during the grace period
+    let locked: Option<(DateTime<Utc>,)> = sqlx::query_as(
+        r#"
+        SELECT MAX(locked_until) FROM username_history
+        WHERE username_skeleton(old_username) = username_skeleton($1)
+          AND wallet_address != $2 AND locked_until > NOW()
+        HAVING COUNT(*) > 0
+        "#
+    )
+    .bind(username)
+    .bind(current_wallet.to_lowercase())
+    .fetch_optional(pool)
+    .await?;
+    if let Some((locked_until,)) = locked {
+        return Ok(Some(format!(
+            "Username '{}' matches a recently changed username and is locked until {}",
+            username,
+            locked_until.format("%Y-%m-%d")
+        )));
+    }
+
     let result = sqlx::query!(
         r#"
-        SELECT wallet_address FROM user_profiles 
-        WHERE LOWER(username) = LOWER($1) AND wallet_address != $2
+        SELECT username FROM user_profiles 
+        WHERE username_skeleton = username_skeleton($1) AND wallet_address != $2
+        LIMIT 1
         "#,
         username,
         current_wallet.to_lowercase()
@@ -127,7 +348,12 @@ pub async fn is_username_available(pool: &DbPool, username: &str, current_wallet
     .fetch_optional(pool)
     .await?;
     
-    Ok(result.is_none())
+    Ok(result.map(|row| match row.username {
+        Some(taken) if taken.eq_ignore_ascii_case(username) => {
+            format!("Username '{}' is already taken", username)
+        }
+        _ => format!("Username '{}' is too similar to an existing username", username),
+    }))
 }
 
 /// Claim or update username
@@ -155,11 +381,12 @@ pub async fn claim_username(
     }
     
     // Check if username is available
-    if !is_username_available(pool, username, &wallet_lower).await? {
-        return Err(anyhow!("Username '{}' is already taken", username));
+    if let Some(reason) = username_unavailable_reason(pool, username, &wallet_lower).await? {
+        return Err(anyhow!(reason));
     }
     
     // Update username
+    let mut tx = pool.begin().await?;
     let updated = sqlx::query_as::<_, UserProfile>(
         r#"
         UPDATE user_profiles
@@ -170,12 +397,45 @@ pub async fn claim_username(
     )
     .bind(username)
     .bind(&wallet_lower)
-    .fetch_one(pool)
+    .fetch_one(&mut *tx)
     .await?;
     
+    if let Some(old_username) = &profile.username {
+        record_username_change(&mut tx, &wallet_lower, old_username, username).await?;
+    }
+    tx.commit().await?;
+    
     Ok(updated)
 }
 
+/// Log a rename so the old name redirects to this profile and stays locked for the
+/// grace period. Case-only changes aren't renames.
+pub async fn record_username_change(
+    conn: &mut sqlx::PgConnection,
+    wallet_address: &str,
+    old_username: &str,
+    new_username: &str,
+) -> Result<()> {
+    if old_username.eq_ignore_ascii_case(new_username) {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO username_history (wallet_address, old_username, new_username, locked_until)
+        VALUES ($1, $2, $3, NOW() + make_interval(days => $4))
+        "#
+    )
+    .bind(wallet_address.to_lowercase())
+    .bind(old_username)
+    .bind(new_username)
+    .bind(USERNAME_REDIRECT_DAYS as i32)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
 /// Update user profile (display name, avatar, bio)
 pub async fn update_profile(
     pool: &DbPool,
@@ -190,18 +450,51 @@ pub async fn update_profile(
     if let Some(new_username) = &req.username {
         validate_username(new_username)?;
         
-        if let Some(current_username) = &profile.username {
-            if current_username.to_lowercase() != new_username.to_lowercase() {
+        let changing = profile
+            .username
+            .as_ref()
+            .map_or(true, |current| current.to_lowercase() != new_username.to_lowercase());
+        if changing {
+            if profile.username.is_some() {
                 can_change_username(profile.last_username_change)?;
-                
-                if !is_username_available(pool, new_username, &wallet_lower).await? {
-                    return Err(anyhow!("Username '{}' is already taken", new_username));
-                }
             }
+            
+            if let Some(reason) = username_unavailable_reason(pool, new_username, &wallet_lower).await? {
+                return Err(anyhow!(reason));
+            }
+        }
+    }
+    
+    // Avatars are served from our own storage, not arbitrary (tracking) URLs
+    if req
+        .avatar_url
+        .as_deref()
+        .is_some_and(|url| !url.is_empty() && !image_service::is_uploaded_image_url(url))
+    {
+        return Err(anyhow!("avatar_url must be an image uploaded through /api/uploads/images"));
+    }
+    
+    // A basename is only accepted once it resolves to this wallet on Base
+    if let Some(basename) = &req.basename {
+        let basename = basename.trim();
+        if basename.is_empty() {
+            store_basename(pool, &wallet_lower, None).await?;
+        } else {
+            let basename = basename_service::normalize_basename(basename);
+            let wallet: Address = wallet_lower.parse().map_err(|_| anyhow!("Invalid wallet address"))?;
+            let provider = token_gate_service::base_provider().map_err(|e| anyhow!("{}", e))?;
+            let owned = basename_service::basename_owned_by(&provider, &basename, wallet, None)
+                .await
+                .map_err(|e| anyhow!("Could not verify basename: {}", e))?;
+            if !owned {
+                return Err(anyhow!("'{}' does not resolve to your wallet on Base", basename));
+            }
+            store_basename(pool, &wallet_lower, Some(&basename)).await?;
         }
     }
     
     // Build dynamic update query
+    let mut tx = pool.begin().await?;
     let updated = sqlx::query_as::<_, UserProfile>(
         r#"
         UPDATE user_profiles
@@ -211,8 +504,8 @@ pub async fn update_profile(
             avatar_url = COALESCE($3, avatar_url),
             bio = COALESCE($4, bio),
             last_username_change = CASE WHEN $1 IS NOT NULL AND $1 != username THEN NOW() ELSE last_username_change END,
-            basename = COALESCE($6, basename),
-            basename_discoverable = COALESCE($7, basename_discoverable)
+            basename_discoverable = COALESCE($6, basename_discoverable),
+            ens_discoverable = COALESCE($7, ens_discoverable)
         WHERE wallet_address = $5
         RETURNING *
         "#
@@ -222,50 +515,309 @@ pub async fn update_profile(
     .bind(&req.avatar_url)
     .bind(&req.bio)
     .bind(&wallet_lower)
-    .bind(&req.basename)
     .bind(req.basename_discoverable)
-    .fetch_one(pool)
+    .bind(req.ens_discoverable)
+    .fetch_one(&mut *tx)
     .await?;
     
+    if let (Some(old_username), Some(new_username)) = (&profile.username, &req.username) {
+        record_username_change(&mut tx, &wallet_lower, old_username, new_username).await?;
+    }
+    tx.commit().await?;
+    
     Ok(updated)
 }
 
-/// Search profiles by username, wallet address, inbox_id, or discoverable basename
-pub async fn search_profiles(pool: &DbPool, query: &str, limit: i64) -> Result<Vec<SearchResult>> {
-    let search_pattern = format!("%{}%", query.to_lowercase());
-    // Strip .base.eth suffix if present so "alice.base.eth" matches stored "alice.base.eth"
-    let basename_pattern = format!("%{}%", query.to_lowercase().trim_end_matches(".base.eth"));
-    
+/// Message a wallet signs to start a profile session
+pub fn sign_in_message(nonce: &str) -> String {
+    format!(
+        "Sign this message to sign in to BlocChat.\n\nNonce: {}\n\nThis signature will not trigger any blockchain transaction or cost gas fees.",
+        nonce
+    )
+}
+
+// ── Privacy ──
+
+/// How a profile was looked up, which decides the discoverability flag that applies
+#[derive(Debug, Clone, Copy)]
+pub enum Lookup {
+    Username,
+    Wallet,
+    InboxId, // Inbox ids are only known to people already in a conversation
+}
+
+pub async fn get_privacy_settings(pool: &DbPool, wallet_address: &str) -> Result<PrivacySettings> {
+    let settings = sqlx::query_as::<_, PrivacySettings>(
+        r#"
+        SELECT discoverable_by_username, discoverable_by_wallet, discoverable_by_display_name,
+               profile_visibility, hide_bio_from_non_contacts, hide_avatar_from_non_contacts
+        FROM user_profiles
+        WHERE wallet_address = $1
+        "#
+    )
+    .bind(wallet_address.to_lowercase())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(settings)
+}
+
+pub async fn update_privacy_settings(
+    pool: &DbPool,
+    wallet_address: &str,
+    req: UpdatePrivacyRequest,
+) -> Result<PrivacySettings> {
+    if let Some(visibility) = &req.profile_visibility {
+        if !matches!(visibility.as_str(), "everyone" | "contacts" | "nobody") {
+            return Err(anyhow!("profile_visibility must be 'everyone', 'contacts' or 'nobody'"));
+        }
+    }
+
+    let settings = sqlx::query_as::<_, PrivacySettings>(
+        r#"
+        UPDATE user_profiles
+        SET discoverable_by_username = COALESCE($2, discoverable_by_username),
+            discoverable_by_wallet = COALESCE($3, discoverable_by_wallet),
+            discoverable_by_display_name = COALESCE($4, discoverable_by_display_name),
+            profile_visibility = COALESCE($5, profile_visibility),
+            hide_bio_from_non_contacts = COALESCE($6, hide_bio_from_non_contacts),
+            hide_avatar_from_non_contacts = COALESCE($7, hide_avatar_from_non_contacts)
+        WHERE wallet_address = $1
+        RETURNING discoverable_by_username, discoverable_by_wallet, discoverable_by_display_name,
+                  profile_visibility, hide_bio_from_non_contacts, hide_avatar_from_non_contacts
+        "#
+    )
+    .bind(wallet_address.to_lowercase())
+    .bind(req.discoverable_by_username)
+    .bind(req.discoverable_by_wallet)
+    .bind(req.discoverable_by_display_name)
+    .bind(&req.profile_visibility)
+    .bind(req.hide_bio_from_non_contacts)
+    .bind(req.hide_avatar_from_non_contacts)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(settings)
+}
+
+/// A single profile as `viewer` may see it, or None when it is hidden from them.
+/// Profiles that opted out of discovery by `lookup` are only found by their contacts.
+pub async fn view_profile(
+    pool: &DbPool,
+    viewer: Option<&str>,
+    profile: UserProfile,
+    lookup: Lookup,
+) -> Result<Option<UserProfile>> {
+    let relations = relations_for(pool, viewer, std::slice::from_ref(&profile.wallet_address)).await?;
+    Ok(view(profile, viewer, &relations, lookup))
+}
+
+/// Look up many profiles at once by inbox id and/or wallet, as `viewer` may see them.
+/// Found profiles are keyed by the requested inbox id or (lowercased) wallet address.
+pub async fn get_profiles_batch(
+    pool: &DbPool,
+    viewer: Option<&str>,
+    inbox_ids: &[String],
+    wallet_addresses: &[String],
+) -> Result<BTreeMap<String, UserProfile>> {
+    let wallet_addresses: Vec<String> = wallet_addresses.iter().map(|w| w.to_lowercase()).collect();
     let profiles = sqlx::query_as::<_, UserProfile>(
+        "SELECT * FROM user_profiles WHERE inbox_id = ANY($1) OR wallet_address = ANY($2)"
+    )
+    .bind(inbox_ids)
+    .bind(&wallet_addresses)
+    .fetch_all(pool)
+    .await?;
+
+    let wallets: Vec<String> = profiles.iter().map(|p| p.wallet_address.clone()).collect();
+    let relations = relations_for(pool, viewer, &wallets).await?;
+
+    let mut found = BTreeMap::new();
+    for profile in profiles {
+        if inbox_ids.contains(&profile.inbox_id) {
+            if let Some(visible) = view(profile.clone(), viewer, &relations, Lookup::InboxId) {
+                found.insert(profile.inbox_id.clone(), visible);
+            }
+        }
+        if wallet_addresses.contains(&profile.wallet_address) {
+            if let Some(visible) = view(profile.clone(), viewer, &relations, Lookup::Wallet) {
+                found.insert(profile.wallet_address.clone(), visible);
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Apply each profile's visibility and field hiding for `viewer`, dropping hidden ones
+pub async fn apply_privacy(
+    pool: &DbPool,
+    viewer: Option<&str>,
+    profiles: Vec<UserProfile>,
+) -> Result<Vec<UserProfile>> {
+    let wallets: Vec<String> = profiles.iter().map(|p| p.wallet_address.clone()).collect();
+    let relations = relations_for(pool, viewer, &wallets).await?;
+
+    Ok(profiles
+        .into_iter()
+        .filter_map(|profile| redact(profile, viewer, &relations))
+        .collect())
+}
+
+async fn relations_for(pool: &DbPool, viewer: Option<&str>, wallets: &[String]) -> Result<Relations> {
+    match viewer {
+        Some(viewer) => social_service::relations_with(pool, viewer, wallets).await,
+        None => Ok(Relations::default()),
+    }
+}
+
+/// Profiles that opted out of discovery by `lookup` are only found by their contacts
+fn view(profile: UserProfile, viewer: Option<&str>, relations: &Relations, lookup: Lookup) -> Option<UserProfile> {
+    let discoverable = match lookup {
+        Lookup::Username => profile.discoverable_by_username,
+        Lookup::Wallet => profile.discoverable_by_wallet,
+        Lookup::InboxId => true,
+    };
+    if !discoverable && !is_self(viewer, &profile) && !relations.contacts.contains(&profile.wallet_address) {
+        return None;
+    }
+    redact(profile, viewer, relations)
+}
+
+fn is_self(viewer: Option<&str>, profile: &UserProfile) -> bool {
+    viewer.is_some_and(|v| v.eq_ignore_ascii_case(&profile.wallet_address))
+}
+
+/// Owners see their whole profile. Everyone else is subject to blocks (either way)
+/// and the visibility setting; non-contacts may also lose the bio and avatar.
+fn redact(mut profile: UserProfile, viewer: Option<&str>, relations: &Relations) -> Option<UserProfile> {
+    if is_self(viewer, &profile) {
+        return Some(profile);
+    }
+    if relations.blocked.contains(&profile.wallet_address) {
+        return None;
+    }
+
+    let contact = relations.contacts.contains(&profile.wallet_address);
+    match profile.profile_visibility.as_str() {
+        "everyone" => {}
+        "contacts" if contact => {}
+        _ => return None,
+    }
+
+    if !contact {
+        if profile.hide_bio_from_non_contacts {
+            profile.bio = None;
+        }
+        if profile.hide_avatar_from_non_contacts {
+            profile.avatar_url = None;
+            profile.ens_avatar = None;
+        }
+    }
+    Some(profile)
+}
+
+#[derive(sqlx::FromRow)]
+struct ProfileSearchRow {
+    #[sqlx(flatten)]
+    profile: UserProfile,
+    #[sqlx(flatten)]
+    highlight: SearchHighlight,
+}
+
+/// Fuzzy search over username, display name, wallet address, inbox_id and discoverable
+/// basename / ENS name. Each profile is ranked by its best matching attribute (exact,
+/// prefix, substring, then trigram similarity) plus a follower count boost, with ties
+/// broken so offsets page stably. Username, display name and wallet only match profiles
+/// discoverable that way (or the viewer's contacts), and each result goes through the
+/// profile's privacy settings. Blocks in either direction hide a profile.
+pub async fn search_profiles(
+    pool: &DbPool,
+    query: &str,
+    limit: i64,
+    offset: i64,
+    viewer_wallet: Option<&str>,
+) -> Result<Vec<SearchResult>> {
+    let query = query.trim();
+    let viewer_wallet = viewer_wallet.map(|w| w.to_lowercase());
+
+    // The outer WHERE mirrors the per-field matches so the trigram indexes are used
+    let rows = sqlx::query_as::<_, ProfileSearchRow>(
         r#"
-        SELECT * FROM user_profiles
-        WHERE 
-            LOWER(username) LIKE $1
-            OR LOWER(display_name) LIKE $1
-            OR LOWER(wallet_address) LIKE $1
-            OR inbox_id = $2
-            OR (basename_discoverable = true AND LOWER(basename) LIKE $5)
-        ORDER BY 
-            CASE 
-                WHEN inbox_id = $2 THEN 0
-                WHEN LOWER(username) = $3 THEN 1
-                WHEN LOWER(basename) = $4 THEN 2
-                ELSE 3
-            END,
-            created_at DESC
-        LIMIT $6
+        WITH known AS (
+            SELECT $4::TEXT AS wallet
+            UNION
+            SELECT f.followee_wallet FROM profile_follows f
+            JOIN profile_follows back
+              ON back.follower_wallet = f.followee_wallet AND back.followee_wallet = f.follower_wallet
+            WHERE f.follower_wallet = $4
+        )
+        SELECT p.*, m.match_field, m.match_value, m.match_kind,
+               m.score + $5 * LN(1 + followers.count) AS rank
+        FROM user_profiles p
+        LEFT JOIN known k ON k.wallet = p.wallet_address
+        CROSS JOIN LATERAL (
+            SELECT f.field AS match_field, f.value AS match_value,
+                   search_match_kind(f.value, $1) AS match_kind,
+                   search_match_score(f.value, $1) AS score
+            FROM (VALUES
+                ('inbox_id', CASE WHEN p.inbox_id = $1 THEN p.inbox_id END),
+                ('username', CASE WHEN p.discoverable_by_username OR k.wallet IS NOT NULL THEN p.username END),
+                ('display_name', CASE WHEN p.discoverable_by_display_name OR k.wallet IS NOT NULL THEN p.display_name END),
+                -- Hex addresses only match by substring; fuzzy matches would be noise
+                ('wallet_address', CASE WHEN (p.discoverable_by_wallet OR k.wallet IS NOT NULL)
+                                         AND strpos(p.wallet_address, LOWER($1)) > 0 THEN p.wallet_address END),
+                ('basename', CASE WHEN p.basename_discoverable THEN p.basename END),
+                ('ens_name', CASE WHEN p.ens_discoverable THEN p.ens_name END)
+            ) AS f(field, value)
+            WHERE search_match_kind(f.value, $1) IS NOT NULL
+            ORDER BY score DESC
+            LIMIT 1
+        ) m
+        CROSS JOIN LATERAL (
+            SELECT COUNT(*) AS count FROM profile_follows WHERE followee_wallet = p.wallet_address
+        ) followers
+        WHERE
+            (
+                p.inbox_id = $1
+                OR p.username % $1 OR $1 <% p.username OR p.username ILIKE search_like_pattern($1)
+                OR p.display_name % $1 OR $1 <% p.display_name OR p.display_name ILIKE search_like_pattern($1)
+                OR p.wallet_address ILIKE search_like_pattern($1)
+                OR p.basename % $1 OR $1 <% p.basename OR p.basename ILIKE search_like_pattern($1)
+                OR p.ens_name % $1 OR $1 <% p.ens_name OR p.ens_name ILIKE search_like_pattern($1)
+            )
+            AND (p.profile_visibility = 'everyone' OR k.wallet IS NOT NULL)
+            AND ($4::TEXT IS NULL OR NOT EXISTS (
+                SELECT 1 FROM profile_blocks b
+                WHERE (b.blocker_wallet = $4 AND b.blocked_wallet = p.wallet_address)
+                   OR (b.blocker_wallet = p.wallet_address AND b.blocked_wallet = $4)
+            ))
+        ORDER BY rank DESC, p.created_at DESC, p.id
+        LIMIT $2 OFFSET $3
         "#
     )
-    .bind(&search_pattern)
-    .bind(query)                    // Exact match for inbox_id
-    .bind(query.to_lowercase())     // Exact username match for ranking
-    .bind(query.to_lowercase())     // Exact basename match for ranking
-    .bind(&basename_pattern)        // LIKE match for basename (discoverable only)
+    .bind(query)
     .bind(limit)
+    .bind(offset)
+    .bind(&viewer_wallet)
+    .bind(POPULARITY_WEIGHT)
     .fetch_all(pool)
     .await?;
-    
-    Ok(profiles.into_iter().map(SearchResult::from).collect())
+
+    let mut highlights: HashMap<String, SearchHighlight> = HashMap::new();
+    let mut profiles = Vec::with_capacity(rows.len());
+    for row in rows {
+        highlights.insert(row.profile.wallet_address.clone(), row.highlight);
+        profiles.push(row.profile);
+    }
+
+    let profiles = apply_privacy(pool, viewer_wallet.as_deref(), profiles).await?;
+    Ok(profiles
+        .into_iter()
+        .map(|profile| {
+            let highlight = highlights.remove(&profile.wallet_address);
+            SearchResult { highlight, ..SearchResult::from(profile) }
+        })
+        .collect())
 }
 
 #[cfg(test)]
@@ -0,0 +1,457 @@
+use crate::{
+    db::DbPool,
+    models::{
+        PromotionRedemption, PromotionRequest, PromotionResponse, ShopPromotion,
+    },
+};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use ethers::prelude::U256;
+use uuid::Uuid;
+
+const MIN_CODE_LEN: usize = 3;
+const MAX_CODE_LEN: usize = 40;
+const MAX_RESTRICTED_ITEMS: usize = 100;
+
+/// Fields shared by create and update, validated and converted for storage
+struct PromotionFields {
+    code: String,
+    discount_type: String,
+    percent_off_bps: Option<i32>,
+    amount_off: Option<String>,
+    amount_off_token_address: Option<String>,
+    amount_off_usd_cents: Option<i64>,
+    item_ids: Option<Vec<Uuid>>,
+    max_redemptions: Option<i32>,
+    max_per_wallet: Option<i32>,
+    starts_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    is_active: bool,
+}
+
+/// What a promo code takes off an order, in the unit the order is priced in
+pub enum Discount {
+    Token(U256),
+    UsdCents(i64),
+}
+
+// ── Seller management ──
+
+pub async fn create_promotion(
+    pool: &DbPool,
+    shop_id: &Uuid,
+    seller_address: &str,
+    req: PromotionRequest,
+) -> Result<PromotionResponse> {
+    check_owner(pool, shop_id, seller_address).await?;
+    let fields = validate(req)?;
+    check_items_in_shop(pool, shop_id, &fields.item_ids).await?;
+
+    let promotion = sqlx::query_as::<_, ShopPromotion>(
+        r#"
+        INSERT INTO shop_promotions (shop_id, code, discount_type, percent_off_bps, amount_off,
+                                     amount_off_token_address, amount_off_usd_cents, item_ids,
+                                     max_redemptions, max_per_wallet, starts_at, expires_at, is_active)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        RETURNING *
+        "#,
+    )
+    .bind(shop_id)
+    .bind(&fields.code)
+    .bind(&fields.discount_type)
+    .bind(fields.percent_off_bps)
+    .bind(&fields.amount_off)
+    .bind(&fields.amount_off_token_address)
+    .bind(fields.amount_off_usd_cents)
+    .bind(&fields.item_ids)
+    .bind(fields.max_redemptions)
+    .bind(fields.max_per_wallet)
+    .bind(fields.starts_at)
+    .bind(fields.expires_at)
+    .bind(fields.is_active)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(PromotionResponse::from(promotion))
+}
+
+pub async fn get_promotions(
+    pool: &DbPool,
+    shop_id: &Uuid,
+    seller_address: &str,
+) -> Result<Vec<PromotionResponse>> {
+    check_owner(pool, shop_id, seller_address).await?;
+
+    let promotions = sqlx::query_as::<_, ShopPromotion>(
+        "SELECT * FROM shop_promotions WHERE shop_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(shop_id)
+    .fetch_all(pool)
+    .await?;
+
+    let counts: Vec<(Uuid, i64)> = sqlx::query_as(
+        r#"
+        SELECT r.promotion_id, COUNT(*)
+        FROM shop_promotion_redemptions r
+        JOIN shop_promotions p ON p.id = r.promotion_id
+        JOIN orders o ON o.id = r.order_id
+        WHERE p.shop_id = $1 AND o.status IN ('pending', 'paid', 'fulfilled')
+        GROUP BY r.promotion_id
+        "#,
+    )
+    .bind(shop_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(promotions
+        .into_iter()
+        .map(|promotion| {
+            let count = counts
+                .iter()
+                .find(|(id, _)| *id == promotion.id)
+                .map_or(0, |(_, count)| *count);
+            let mut response = PromotionResponse::from(promotion);
+            response.redemption_count = count;
+            response
+        })
+        .collect())
+}
+
+pub async fn update_promotion(
+    pool: &DbPool,
+    shop_id: &Uuid,
+    promotion_id: &Uuid,
+    seller_address: &str,
+    req: PromotionRequest,
+) -> Result<PromotionResponse> {
+    check_owner(pool, shop_id, seller_address).await?;
+    let fields = validate(req)?;
+    check_items_in_shop(pool, shop_id, &fields.item_ids).await?;
+
+    let promotion = sqlx::query_as::<_, ShopPromotion>(
+        r#"
+        UPDATE shop_promotions
+        SET code = $1, discount_type = $2, percent_off_bps = $3, amount_off = $4,
+            amount_off_token_address = $5, amount_off_usd_cents = $6, item_ids = $7,
+            max_redemptions = $8, max_per_wallet = $9, starts_at = $10, expires_at = $11, is_active = $12
+        WHERE id = $13 AND shop_id = $14
+        RETURNING *
+        "#,
+    )
+    .bind(&fields.code)
+    .bind(&fields.discount_type)
+    .bind(fields.percent_off_bps)
+    .bind(&fields.amount_off)
+    .bind(&fields.amount_off_token_address)
+    .bind(fields.amount_off_usd_cents)
+    .bind(&fields.item_ids)
+    .bind(fields.max_redemptions)
+    .bind(fields.max_per_wallet)
+    .bind(fields.starts_at)
+    .bind(fields.expires_at)
+    .bind(fields.is_active)
+    .bind(promotion_id)
+    .bind(shop_id)
+    .fetch_one(pool)
+    .await?;
+
+    let mut response = PromotionResponse::from(promotion);
+    response.redemption_count = redemption_count(pool, promotion_id, None).await?;
+    Ok(response)
+}
+
+/// Orders keep their promo_code and discount; only the promotion_id link is cleared
+pub async fn delete_promotion(
+    pool: &DbPool,
+    shop_id: &Uuid,
+    promotion_id: &Uuid,
+    seller_address: &str,
+) -> Result<()> {
+    check_owner(pool, shop_id, seller_address).await?;
+
+    let result = sqlx::query("DELETE FROM shop_promotions WHERE id = $1 AND shop_id = $2")
+        .bind(promotion_id)
+        .bind(shop_id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound.into());
+    }
+    Ok(())
+}
+
+pub async fn get_redemptions(
+    pool: &DbPool,
+    shop_id: &Uuid,
+    promotion_id: &Uuid,
+    seller_address: &str,
+) -> Result<Vec<PromotionRedemption>> {
+    check_owner(pool, shop_id, seller_address).await?;
+
+    let redemptions = sqlx::query_as::<_, PromotionRedemption>(
+        r#"
+        SELECT r.order_id, r.buyer_address, r.discount_amount, r.discount_usd_cents,
+               o.status AS order_status, r.created_at
+        FROM shop_promotion_redemptions r
+        JOIN shop_promotions p ON p.id = r.promotion_id
+        JOIN orders o ON o.id = r.order_id
+        WHERE r.promotion_id = $1 AND p.shop_id = $2
+        ORDER BY r.created_at DESC
+        "#,
+    )
+    .bind(promotion_id)
+    .bind(shop_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(redemptions)
+}
+
+/// `seller_address` is the signed-in wallet; promotions list the codes themselves,
+/// so only the shop owner may read or change them
+async fn check_owner(pool: &DbPool, shop_id: &Uuid, seller_address: &str) -> Result<()> {
+    let (owner_address,): (String,) = sqlx::query_as("SELECT owner_address FROM shops WHERE id = $1")
+        .bind(shop_id)
+        .fetch_one(pool)
+        .await?;
+
+    if !owner_address.eq_ignore_ascii_case(seller_address) {
+        return Err(anyhow!("Only the shop owner can manage promotions"));
+    }
+    Ok(())
+}
+
+async fn check_items_in_shop(pool: &DbPool, shop_id: &Uuid, item_ids: &Option<Vec<Uuid>>) -> Result<()> {
+    let Some(item_ids) = item_ids else {
+        return Ok(());
+    };
+    let (found,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM shop_items WHERE shop_id = $1 AND id = ANY($2)",
+    )
+    .bind(shop_id)
+    .bind(item_ids)
+    .fetch_one(pool)
+    .await?;
+
+    if found as usize != item_ids.len() {
+        return Err(anyhow!("Promotions can only be restricted to items in this shop"));
+    }
+    Ok(())
+}
+
+fn validate(req: PromotionRequest) -> Result<PromotionFields> {
+    let code = req.code.trim().to_string();
+    let valid_code = (MIN_CODE_LEN..=MAX_CODE_LEN).contains(&code.len())
+        && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_code {
+        return Err(anyhow!(
+            "Codes must be {}-{} letters, digits, '-' or '_'",
+            MIN_CODE_LEN, MAX_CODE_LEN
+        ));
+    }
+
+    let mut fields = PromotionFields {
+        code,
+        discount_type: req.discount_type,
+        percent_off_bps: None,
+        amount_off: None,
+        amount_off_token_address: None,
+        amount_off_usd_cents: None,
+        item_ids: req
+            .item_ids
+            .map(|mut ids| {
+                ids.sort();
+                ids.dedup();
+                ids
+            })
+            .filter(|ids| !ids.is_empty()),
+        max_redemptions: req.max_redemptions,
+        max_per_wallet: req.max_per_wallet,
+        starts_at: req.starts_at,
+        expires_at: req.expires_at,
+        is_active: req.is_active,
+    };
+
+    match fields.discount_type.as_str() {
+        "percentage" => {
+            if !req.percent_off_bps.is_some_and(|bps| (1..=10_000).contains(&bps)) {
+                return Err(anyhow!("Percentage promotions need percent_off_bps between 1 and 10000"));
+            }
+            fields.percent_off_bps = req.percent_off_bps;
+        }
+        "fixed" => match (req.amount_off, req.amount_off_usd) {
+            (Some(amount), None) => {
+                let parsed = U256::from_dec_str(&amount)
+                    .map_err(|_| anyhow!("amount_off must be an integer in the token's smallest unit"))?;
+                if parsed.is_zero() {
+                    return Err(anyhow!("amount_off must be greater than zero"));
+                }
+                if let Some(address) = &req.amount_off_token_address {
+                    address
+                        .parse::<ethers::types::Address>()
+                        .map_err(|_| anyhow!("Invalid amount_off_token_address"))?;
+                }
+                fields.amount_off = Some(parsed.to_string());
+                fields.amount_off_token_address = req.amount_off_token_address.map(|a| a.to_lowercase());
+            }
+            (None, Some(usd)) if usd.is_finite() && usd >= 0.01 => {
+                fields.amount_off_usd_cents = Some((usd * 100.0).round() as i64);
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Fixed promotions need either amount_off (token units) or amount_off_usd"
+                ))
+            }
+        },
+        _ => return Err(anyhow!("discount_type must be 'percentage' or 'fixed'")),
+    }
+
+    if fields.item_ids.as_ref().is_some_and(|ids| ids.len() > MAX_RESTRICTED_ITEMS) {
+        return Err(anyhow!("A promotion can be restricted to at most {} items", MAX_RESTRICTED_ITEMS));
+    }
+    if fields.max_redemptions.is_some_and(|n| n < 1) || fields.max_per_wallet.is_some_and(|n| n < 1) {
+        return Err(anyhow!("Usage limits must be at least 1"));
+    }
+    if let (Some(starts_at), Some(expires_at)) = (fields.starts_at, fields.expires_at) {
+        if starts_at >= expires_at {
+            return Err(anyhow!("starts_at must be before expires_at"));
+        }
+    }
+
+    Ok(fields)
+}
+
+// ── Checkout ──
+
+/// Redemptions that still count toward limits, optionally for one wallet
+async fn redemption_count<'e, E>(executor: E, promotion_id: &Uuid, buyer_address: Option<&str>) -> Result<i64>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let (count,): (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*)
+        FROM shop_promotion_redemptions r
+        JOIN orders o ON o.id = r.order_id
+        WHERE r.promotion_id = $1
+          AND o.status IN ('pending', 'paid', 'fulfilled')
+          AND ($2::TEXT IS NULL OR LOWER(r.buyer_address) = LOWER($2))
+        "#,
+    )
+    .bind(promotion_id)
+    .bind(buyer_address)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(count)
+}
+
+/// Find and lock a shop's promo code for checkout and check that this buyer can
+/// still use it. The row lock serialises concurrent checkouts with the same code
+/// so usage limits can't be overshot.
+pub async fn lock_for_checkout(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    shop_id: &Uuid,
+    code: &str,
+    buyer_address: &str,
+) -> Result<ShopPromotion> {
+    let promotion = sqlx::query_as::<_, ShopPromotion>(
+        "SELECT * FROM shop_promotions WHERE shop_id = $1 AND UPPER(code) = UPPER($2) FOR UPDATE",
+    )
+    .bind(shop_id)
+    .bind(code.trim())
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or_else(|| anyhow!("Promo code '{}' is not valid for this shop", code.trim()))?;
+
+    let now = Utc::now();
+    if !promotion.is_active || promotion.starts_at.is_some_and(|t| now < t) {
+        return Err(anyhow!("Promo code '{}' is not active", promotion.code));
+    }
+    if promotion.expires_at.is_some_and(|t| now >= t) {
+        return Err(anyhow!("Promo code '{}' has expired", promotion.code));
+    }
+    if let Some(max_redemptions) = promotion.max_redemptions {
+        if redemption_count(&mut **tx, &promotion.id, None).await? >= max_redemptions as i64 {
+            return Err(anyhow!("Promo code '{}' has been fully redeemed", promotion.code));
+        }
+    }
+    if let Some(max_per_wallet) = promotion.max_per_wallet {
+        if redemption_count(&mut **tx, &promotion.id, Some(buyer_address)).await? >= max_per_wallet as i64 {
+            return Err(anyhow!("You have already used promo code '{}'", promotion.code));
+        }
+    }
+
+    Ok(promotion)
+}
+
+pub fn applies_to(promotion: &ShopPromotion, item_id: &Uuid) -> bool {
+    promotion.item_ids.as_ref().map_or(true, |ids| ids.contains(item_id))
+}
+
+/// Discount on the eligible part of an order. USD-priced orders pass their
+/// eligible subtotal in cents; token-priced ones in the payment token's units.
+pub fn compute_discount(
+    promotion: &ShopPromotion,
+    eligible_usd_cents: Option<i64>,
+    eligible_amount: U256,
+    token_address: Option<&str>,
+) -> Result<Discount> {
+    let discount = match (promotion.discount_type.as_str(), eligible_usd_cents) {
+        ("percentage", Some(cents)) => {
+            Discount::UsdCents(cents * promotion.percent_off_bps.unwrap_or(0) as i64 / 10_000)
+        }
+        ("percentage", None) => Discount::Token(
+            eligible_amount * U256::from(promotion.percent_off_bps.unwrap_or(0)) / U256::from(10_000u64),
+        ),
+        (_, Some(cents)) => {
+            let off = promotion
+                .amount_off_usd_cents
+                .ok_or_else(|| anyhow!("Promo code '{}' only applies to token-priced items", promotion.code))?;
+            Discount::UsdCents(off.min(cents))
+        }
+        (_, None) => {
+            let off = promotion
+                .amount_off
+                .as_deref()
+                .and_then(|a| U256::from_dec_str(a).ok())
+                .ok_or_else(|| anyhow!("Promo code '{}' only applies to USD-priced items", promotion.code))?;
+            let same_token = match (promotion.amount_off_token_address.as_deref(), token_address) {
+                (None, None) => true,
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                _ => false,
+            };
+            if !same_token {
+                return Err(anyhow!("Promo code '{}' can't be used with this order's token", promotion.code));
+            }
+            Discount::Token(off.min(eligible_amount))
+        }
+    };
+    Ok(discount)
+}
+
+pub async fn record_redemption(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    promotion_id: &Uuid,
+    order_id: &Uuid,
+    buyer_address: &str,
+    discount_amount: U256,
+    discount_usd_cents: Option<i64>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO shop_promotion_redemptions (promotion_id, order_id, buyer_address,
+                                                discount_amount, discount_usd_cents)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(promotion_id)
+    .bind(order_id)
+    .bind(buyer_address.to_lowercase())
+    .bind(discount_amount.to_string())
+    .bind(discount_usd_cents)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
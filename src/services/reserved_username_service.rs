@@ -0,0 +1,201 @@
+use crate::{
+    db::DbPool,
+    models::{ReserveUsernameRequest, ReservedUsername, UserProfile},
+    services::profile_service,
+};
+use anyhow::{anyhow, Result};
+
+const MAX_REASON_LEN: usize = 500;
+
+/// Why `wallet_address` may not claim `username` because of the reserved list, if
+/// it may not. Matching is by skeleton, so lookalikes of a reserved name are caught.
+pub async fn blocking_reason(pool: &DbPool, username: &str, wallet_address: &str) -> Result<Option<String>> {
+    let reserved = sqlx::query_as::<_, ReservedUsername>(
+        "SELECT * FROM reserved_usernames WHERE skeleton = username_skeleton($1)",
+    )
+    .bind(username)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(reserved.and_then(|r| match r.kind.as_str() {
+        "blocked" => Some(format!("Username '{}' is not available", username)),
+        _ if r
+            .assigned_wallet
+            .as_deref()
+            .is_some_and(|w| w.eq_ignore_ascii_case(wallet_address)) =>
+        {
+            None
+        }
+        _ => Some(format!("Username '{}' is reserved", username)),
+    }))
+}
+
+pub async fn list_reserved(pool: &DbPool) -> Result<Vec<ReservedUsername>> {
+    let reserved = sqlx::query_as::<_, ReservedUsername>("SELECT * FROM reserved_usernames ORDER BY name")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(reserved)
+}
+
+/// Reserve or block a name. Fails with a unique violation when a name with the same
+/// skeleton is already on the list.
+pub async fn reserve(pool: &DbPool, req: ReserveUsernameRequest, admin_address: &str) -> Result<ReservedUsername> {
+    let name = req.name.trim().to_lowercase();
+    profile_service::validate_username(&name)?;
+    if req.kind != "reserved" && req.kind != "blocked" {
+        return Err(anyhow!("kind must be 'reserved' or 'blocked'"));
+    }
+    if req.kind == "blocked" && req.wallet_address.is_some() {
+        return Err(anyhow!("Blocked names can't be assigned to a wallet"));
+    }
+    if req.reason.as_ref().is_some_and(|r| r.chars().count() > MAX_REASON_LEN) {
+        return Err(anyhow!("Reason can be at most {} characters", MAX_REASON_LEN));
+    }
+    if let Some(wallet) = &req.wallet_address {
+        wallet
+            .parse::<ethers::types::Address>()
+            .map_err(|_| anyhow!("Invalid wallet address"))?;
+    }
+
+    let reserved = sqlx::query_as::<_, ReservedUsername>(
+        r#"
+        INSERT INTO reserved_usernames (name, kind, reason, assigned_wallet, created_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+    )
+    .bind(&name)
+    .bind(&req.kind)
+    .bind(&req.reason)
+    .bind(req.wallet_address.map(|w| w.to_lowercase()))
+    .bind(admin_address.to_lowercase())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(reserved)
+}
+
+/// Take a name (or any lookalike of it) off the list
+pub async fn release(pool: &DbPool, name: &str) -> Result<()> {
+    sqlx::query("DELETE FROM reserved_usernames WHERE skeleton = username_skeleton($1) RETURNING id")
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Hand a reserved name to a verified brand's wallet and set it as that profile's
+/// username right away, bypassing the change cooldown
+pub async fn assign(
+    pool: &DbPool,
+    name: &str,
+    wallet_address: &str,
+    admin_address: &str,
+) -> Result<(ReservedUsername, UserProfile)> {
+    let wallet_lower = wallet_address.to_lowercase();
+    let mut tx = pool.begin().await?;
+
+    let reserved = sqlx::query_as::<_, ReservedUsername>(
+        "SELECT * FROM reserved_usernames WHERE skeleton = username_skeleton($1) FOR UPDATE",
+    )
+    .bind(name)
+    .fetch_one(&mut *tx)
+    .await?;
+    if reserved.kind != "reserved" {
+        return Err(anyhow!("Blocked names can't be assigned"));
+    }
+
+    let holder: Option<(String,)> = sqlx::query_as(
+        r#"
+        SELECT wallet_address FROM user_profiles
+        WHERE username_skeleton = username_skeleton($1) AND wallet_address != $2
+        LIMIT 1
+        "#,
+    )
+    .bind(&reserved.name)
+    .bind(&wallet_lower)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if let Some((holder,)) = holder {
+        return Err(anyhow!("'{}' or a lookalike is already used by {}", reserved.name, holder));
+    }
+
+    let reserved = sqlx::query_as::<_, ReservedUsername>(
+        "UPDATE reserved_usernames SET assigned_wallet = $2 WHERE id = $1 RETURNING *",
+    )
+    .bind(reserved.id)
+    .bind(&wallet_lower)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let (old_username,): (Option<String>,) =
+        sqlx::query_as("SELECT username FROM user_profiles WHERE wallet_address = $1 FOR UPDATE")
+            .bind(&wallet_lower)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow!("No profile exists for {}", wallet_lower))?;
+
+    let profile = sqlx::query_as::<_, UserProfile>(
+        r#"
+        UPDATE user_profiles
+        SET username = $1, last_username_change = NOW()
+        WHERE wallet_address = $2
+        RETURNING *
+        "#,
+    )
+    .bind(&reserved.name)
+    .bind(&wallet_lower)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if let Some(old_username) = &old_username {
+        profile_service::record_username_change(&mut tx, &wallet_lower, old_username, &reserved.name).await?;
+    }
+    tx.commit().await?;
+
+    log::info!("Reserved username '{}' assigned to {} by {}", reserved.name, wallet_lower, admin_address);
+    Ok((reserved, profile))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_pool;
+
+    const WALLET: &str = "0x000000000000000000000000000000000000dead";
+
+    async fn skeleton(pool: &DbPool, name: &str) -> String {
+        let (skeleton,): (String,) = sqlx::query_as("SELECT username_skeleton($1)")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        skeleton
+    }
+
+    /// Runs against a database with the migrations applied:
+    ///   DATABASE_URL=postgres://... cargo test -- --ignored reserved
+    #[tokio::test]
+    #[ignore = "needs a migrated database (DATABASE_URL)"]
+    async fn test_skeleton_folding_and_reserved_names() {
+        let pool = create_pool(&std::env::var("DATABASE_URL").expect("DATABASE_URL")).await.unwrap();
+
+        let coinbase = skeleton(&pool, "coinbase").await;
+        assert_eq!(skeleton(&pool, "c0inbase").await, coinbase);
+        assert_eq!(skeleton(&pool, "co_inbase").await, coinbase);
+        assert_eq!(skeleton(&pool, "C0IN_BASE").await, coinbase);
+        assert_eq!(skeleton(&pool, "rnoderator").await, skeleton(&pool, "moderator").await);
+        assert_ne!(skeleton(&pool, "alice").await, coinbase);
+
+        // Seeded by the migration: 'coinbase' is reserved, 'admin' is blocked
+        let reason = blocking_reason(&pool, "c0in_base", WALLET).await.unwrap();
+        assert_eq!(reason.as_deref(), Some("Username 'c0in_base' is reserved"));
+        let reason = blocking_reason(&pool, "adm1n", WALLET).await.unwrap();
+        assert_eq!(reason.as_deref(), Some("Username 'adm1n' is not available"));
+        let reason = blocking_reason(&pool, "rnoderator", WALLET).await.unwrap();
+        assert_eq!(reason.as_deref(), Some("Username 'rnoderator' is not available"));
+        assert!(blocking_reason(&pool, "alice_wonder", WALLET).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    db::DbPool,
+    models::{
+        AdminReviewResponse, CreateReviewRequest, NonceStore, Order, OrderStatus, ReviewReplyRequest,
+        ReviewResponse, ShopItemReview,
+    },
+    services::admin_service,
+};
+use anyhow::{anyhow, Result};
+use uuid::Uuid;
+
+const MAX_REVIEW_LEN: usize = 2000;
+const MAX_REPLY_LEN: usize = 1000;
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+pub fn review_message(item_id: &Uuid, order_id: &Uuid, nonce: &str) -> String {
+    format!(
+        "Sign this message to review your BlocChat purchase.\n\nItem: {}\nOrder: {}\nNonce: {}\n\nThis signature will not trigger any blockchain transaction or cost gas fees.",
+        item_id, order_id, nonce
+    )
+}
+
+/// Create a review for an item the signer bought in a paid order. One review per
+/// item per order; refunded orders can't be reviewed. The order must have been paid
+/// with a matched transaction: free orders (a 100% promo or discount) would let a
+/// seller give themselves verified reviews at no cost.
+pub async fn create_review(
+    pool: &DbPool,
+    nonce_store: &NonceStore,
+    item_id: &Uuid,
+    req: CreateReviewRequest,
+) -> Result<ReviewResponse> {
+    if !(1..=5).contains(&req.rating) {
+        return Err(anyhow!("Rating must be between 1 and 5 stars"));
+    }
+    let body = req.body.map(|b| b.trim().to_string()).filter(|b| !b.is_empty());
+    if body.as_ref().is_some_and(|b| b.chars().count() > MAX_REVIEW_LEN) {
+        return Err(anyhow!("Reviews can be at most {} characters", MAX_REVIEW_LEN));
+    }
+
+    admin_service::verify_nonce(nonce_store, &req.wallet_address, &req.nonce)?;
+    let message = review_message(item_id, &req.order_id, &req.nonce);
+    if !admin_service::verify_signature(&req.wallet_address, &message, &req.signature)? {
+        return Err(anyhow!("Signature does not match wallet"));
+    }
+
+    let order = sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = $1")
+        .bind(req.order_id)
+        .fetch_one(pool)
+        .await?;
+
+    if !order.buyer_address.eq_ignore_ascii_case(&req.wallet_address) {
+        return Err(anyhow!("Only the buyer can review this order"));
+    }
+    if !matches!(order.status, OrderStatus::Paid | OrderStatus::Fulfilled) {
+        return Err(anyhow!("Only paid orders can be reviewed"));
+    }
+    if order.tx_hash.is_none() {
+        return Err(anyhow!("Free orders can't be reviewed"));
+    }
+
+    let (shop_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        SELECT si.shop_id FROM order_items oi
+        JOIN shop_items si ON si.id = oi.item_id
+        WHERE oi.order_id = $1 AND oi.item_id = $2
+        "#,
+    )
+    .bind(req.order_id)
+    .bind(item_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| anyhow!("This item is not part of the order"))?;
+
+    let result = sqlx::query_as::<_, ShopItemReview>(
+        r#"
+        INSERT INTO shop_item_reviews (item_id, shop_id, order_id, buyer_address, rating, body)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(item_id)
+    .bind(shop_id)
+    .bind(req.order_id)
+    .bind(order.buyer_address.to_lowercase())
+    .bind(req.rating)
+    .bind(&body)
+    .fetch_one(pool)
+    .await;
+
+    match result {
+        Ok(review) => Ok(ReviewResponse::from(review)),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            Err(anyhow!("You have already reviewed this item for this order"))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Visible reviews for an item, newest first
+pub async fn get_item_reviews(
+    pool: &DbPool,
+    item_id: &Uuid,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<ReviewResponse>> {
+    let reviews = sqlx::query_as::<_, ShopItemReview>(
+        r#"
+        SELECT * FROM shop_item_reviews
+        WHERE item_id = $1 AND NOT is_hidden
+        ORDER BY created_at DESC, id
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(item_id)
+    .bind(limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE))
+    .bind(offset.unwrap_or(0).max(0))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reviews.into_iter().map(ReviewResponse::from).collect())
+}
+
+/// Set or replace the seller's public reply to a review. `seller_address` is the
+/// signed-in wallet and must own the reviewed shop.
+pub async fn reply_to_review(
+    pool: &DbPool,
+    review_id: &Uuid,
+    seller_address: &str,
+    req: ReviewReplyRequest,
+) -> Result<ReviewResponse> {
+    let reply = req.reply.trim();
+    if reply.is_empty() || reply.chars().count() > MAX_REPLY_LEN {
+        return Err(anyhow!("Replies must be 1-{} characters", MAX_REPLY_LEN));
+    }
+
+    let (owner_address,): (String,) = sqlx::query_as(
+        r#"
+        SELECT s.owner_address FROM shop_item_reviews r
+        JOIN shops s ON s.id = r.shop_id
+        WHERE r.id = $1
+        "#,
+    )
+    .bind(review_id)
+    .fetch_one(pool)
+    .await?;
+    if !owner_address.eq_ignore_ascii_case(seller_address) {
+        return Err(anyhow!("Only the shop owner can reply to reviews"));
+    }
+
+    let review = sqlx::query_as::<_, ShopItemReview>(
+        r#"
+        UPDATE shop_item_reviews SET seller_reply = $2, seller_replied_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(review_id)
+    .bind(reply)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(ReviewResponse::from(review))
+}
+
+// ── Moderation ──
+
+pub async fn get_reviews_for_moderation(
+    pool: &DbPool,
+    hidden: Option<bool>,
+    limit: i64,
+) -> Result<Vec<AdminReviewResponse>> {
+    let reviews = sqlx::query_as::<_, ShopItemReview>(
+        r#"
+        SELECT * FROM shop_item_reviews
+        WHERE ($1::BOOLEAN IS NULL OR is_hidden = $1)
+        ORDER BY created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(hidden)
+    .bind(limit.clamp(1, MAX_PAGE_SIZE))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reviews.into_iter().map(AdminReviewResponse::from).collect())
+}
+
+pub async fn set_review_hidden(
+    pool: &DbPool,
+    review_id: &Uuid,
+    hidden: bool,
+    reason: Option<String>,
+    admin_address: &str,
+) -> Result<AdminReviewResponse> {
+    let review = sqlx::query_as::<_, ShopItemReview>(
+        r#"
+        UPDATE shop_item_reviews
+        SET is_hidden = $2,
+            hidden_reason = CASE WHEN $2 THEN $3 END,
+            hidden_by = CASE WHEN $2 THEN $4 END,
+            hidden_at = CASE WHEN $2 THEN NOW() END
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(review_id)
+    .bind(hidden)
+    .bind(reason)
+    .bind(admin_address.to_lowercase())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(AdminReviewResponse::from(review))
+}
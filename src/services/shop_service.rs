@@ -5,6 +5,9 @@ use crate::models::{
 use sqlx::PgPool;
 use uuid::Uuid;
 
+const MAX_CATEGORY_LEN: usize = 50;
+const MAX_TAG_LEN: usize = 30;
+
 pub async fn create_shop(
     pool: &PgPool,
     conversation_id: &str,
@@ -47,6 +50,7 @@ pub async fn get_shops_by_conversation(
         let items = get_shop_items(pool, &shop.id).await?;
         let mut shop_response = ShopResponse::from(shop);
         shop_response.items = items;
+        set_shop_rating(&mut shop_response);
         shop_responses.push(shop_response);
     }
 
@@ -67,6 +71,7 @@ pub async fn get_shop(pool: &PgPool, shop_id: &Uuid) -> Result<ShopResponse, sql
     let items = get_shop_items(pool, shop_id).await?;
     let mut shop_response = ShopResponse::from(shop);
     shop_response.items = items;
+    set_shop_rating(&mut shop_response);
 
     Ok(shop_response)
 }
@@ -92,6 +97,7 @@ pub async fn update_shop(
     let items = get_shop_items(pool, shop_id).await?;
     let mut shop_response = ShopResponse::from(shop);
     shop_response.items = items;
+    set_shop_rating(&mut shop_response);
 
     Ok(shop_response)
 }
@@ -119,8 +125,10 @@ pub async fn create_item(
 ) -> Result<ItemResponse, sqlx::Error> {
     let item = sqlx::query_as::<_, ShopItem>(
         r#"
-        INSERT INTO shop_items (shop_id, name, description, price, token_address, token_symbol, image_url)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO shop_items (shop_id, name, description, price, token_address, token_symbol, image_url,
+                                stock, max_per_wallet, sale_starts_at, sale_ends_at, price_usd_cents,
+                                category, tags)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
         RETURNING *
         "#,
     )
@@ -131,6 +139,13 @@ pub async fn create_item(
     .bind(&req.token_address)
     .bind(&req.token_symbol)
     .bind(&req.image_url)
+    .bind(req.stock)
+    .bind(req.max_per_wallet)
+    .bind(req.sale_starts_at)
+    .bind(req.sale_ends_at)
+    .bind(req.price_usd.map(usd_to_cents))
+    .bind(normalize_category(req.category))
+    .bind(normalize_tags(req.tags))
     .fetch_one(pool)
     .await?;
 
@@ -152,7 +167,44 @@ pub async fn get_shop_items(
     .fetch_all(pool)
     .await?;
 
-    Ok(items.into_iter().map(ItemResponse::from).collect())
+    // Visible reviews only, so moderation takes effect on ratings right away
+    let ratings: Vec<(Uuid, f64, i64)> = sqlx::query_as(
+        r#"
+        SELECT item_id, AVG(rating)::FLOAT8, COUNT(*)
+        FROM shop_item_reviews
+        WHERE shop_id = $1 AND NOT is_hidden
+        GROUP BY item_id
+        "#,
+    )
+    .bind(shop_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let rating = ratings.iter().find(|(id, _, _)| *id == item.id);
+            let mut response = ItemResponse::from(item);
+            if let Some((_, average, count)) = rating {
+                response.average_rating = Some(*average);
+                response.review_count = *count;
+            }
+            response
+        })
+        .collect())
+}
+
+/// Shop rating is the review-weighted average of its items
+fn set_shop_rating(shop: &mut ShopResponse) {
+    let review_count: i64 = shop.items.iter().map(|i| i.review_count).sum();
+    let rating_sum: f64 = shop
+        .items
+        .iter()
+        .filter_map(|i| i.average_rating.map(|avg| avg * i.review_count as f64))
+        .sum();
+
+    shop.review_count = review_count;
+    shop.average_rating = (review_count > 0).then(|| rating_sum / review_count as f64);
 }
 
 pub async fn update_item(
@@ -163,8 +215,10 @@ pub async fn update_item(
     let item = sqlx::query_as::<_, ShopItem>(
         r#"
         UPDATE shop_items
-        SET name = $1, description = $2, price = $3, token_address = $4, token_symbol = $5, image_url = $6, updated_at = CURRENT_TIMESTAMP
-        WHERE id = $7
+        SET name = $1, description = $2, price = $3, token_address = $4, token_symbol = $5, image_url = $6,
+            stock = $7, max_per_wallet = $8, sale_starts_at = $9, sale_ends_at = $10, price_usd_cents = $11,
+            category = $12, tags = $13, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $14
         RETURNING *
         "#,
     )
@@ -174,6 +228,13 @@ pub async fn update_item(
     .bind(&req.token_address)
     .bind(&req.token_symbol)
     .bind(&req.image_url)
+    .bind(req.stock)
+    .bind(req.max_per_wallet)
+    .bind(req.sale_starts_at)
+    .bind(req.sale_ends_at)
+    .bind(req.price_usd.map(usd_to_cents))
+    .bind(normalize_category(req.category))
+    .bind(normalize_tags(req.tags))
     .bind(item_id)
     .fetch_one(pool)
     .await?;
@@ -181,6 +242,42 @@ pub async fn update_item(
     Ok(ItemResponse::from(item))
 }
 
+/// Non-positive or non-finite prices become 0 cents and are rejected by the column check
+fn usd_to_cents(usd: f64) -> i64 {
+    if usd.is_finite() && usd > 0.0 {
+        (usd * 100.0).round() as i64
+    } else {
+        0
+    }
+}
+
+/// Categories are stored as lowercase slugs ("Digital Art" → "digital-art")
+pub fn normalize_category(category: Option<String>) -> Option<String> {
+    category
+        .map(|c| {
+            let words: Vec<String> = c
+                .split(|ch: char| ch.is_whitespace() || ch == '-' || ch == '_')
+                .map(|w| w.chars().filter(|ch| ch.is_ascii_alphanumeric()).collect::<String>().to_lowercase())
+                .filter(|w| !w.is_empty())
+                .collect();
+            words.join("-").chars().take(MAX_CATEGORY_LEN).collect::<String>()
+        })
+        .map(|c| c.trim_end_matches('-').to_string())
+        .filter(|c| !c.is_empty())
+}
+
+/// Lowercase, trimmed and deduplicated; the column check caps the count
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && tag.len() <= MAX_TAG_LEN && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
 pub async fn delete_item(pool: &PgPool, item_id: &Uuid) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
@@ -0,0 +1,399 @@
+use crate::{
+    db::DbPool,
+    models::{
+        FollowSuggestion, RelationshipResponse, SearchResult, SocialListEntry, SocialListQuery, SocialListResponse,
+        UserProfile,
+    },
+    services::profile_service,
+};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+const MAX_SUGGESTIONS: i64 = 50;
+
+#[derive(sqlx::FromRow)]
+struct SocialRow {
+    #[sqlx(flatten)]
+    profile: UserProfile,
+    since: DateTime<Utc>,
+    total: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct SuggestionRow {
+    #[sqlx(flatten)]
+    profile: UserProfile,
+    mutual_count: i64,
+    follows_you: bool,
+}
+
+/// Which side of the follows / blocks table a list reads
+enum Edge {
+    Followers,
+    Following,
+    Contacts,
+    Blocked,
+}
+
+// ── Follows ──
+
+pub async fn follow(pool: &DbPool, wallet_address: &str, target_wallet: &str) -> Result<RelationshipResponse> {
+    let (wallet, target) = pair(wallet_address, target_wallet)?;
+    ensure_profiles(pool, &wallet, &target).await?;
+
+    let (blocked,): (bool,) = sqlx::query_as(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM profile_blocks
+            WHERE (blocker_wallet = $1 AND blocked_wallet = $2) OR (blocker_wallet = $2 AND blocked_wallet = $1)
+        )
+        "#,
+    )
+    .bind(&wallet)
+    .bind(&target)
+    .fetch_one(pool)
+    .await?;
+    if blocked {
+        return Err(anyhow!("You can't follow this user"));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO profile_follows (follower_wallet, followee_wallet)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&wallet)
+    .bind(&target)
+    .execute(pool)
+    .await?;
+
+    get_relationship(pool, &wallet, &target).await
+}
+
+pub async fn unfollow(pool: &DbPool, wallet_address: &str, target_wallet: &str) -> Result<RelationshipResponse> {
+    let (wallet, target) = pair(wallet_address, target_wallet)?;
+
+    sqlx::query("DELETE FROM profile_follows WHERE follower_wallet = $1 AND followee_wallet = $2")
+        .bind(&wallet)
+        .bind(&target)
+        .execute(pool)
+        .await?;
+
+    get_relationship(pool, &wallet, &target).await
+}
+
+// ── Blocks ──
+
+/// Block a user. Follows between the two are dropped in both directions.
+pub async fn block(pool: &DbPool, wallet_address: &str, target_wallet: &str) -> Result<RelationshipResponse> {
+    let (wallet, target) = pair(wallet_address, target_wallet)?;
+    ensure_profiles(pool, &wallet, &target).await?;
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO profile_blocks (blocker_wallet, blocked_wallet)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&wallet)
+    .bind(&target)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM profile_follows
+        WHERE (follower_wallet = $1 AND followee_wallet = $2) OR (follower_wallet = $2 AND followee_wallet = $1)
+        "#,
+    )
+    .bind(&wallet)
+    .bind(&target)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    get_relationship(pool, &wallet, &target).await
+}
+
+pub async fn unblock(pool: &DbPool, wallet_address: &str, target_wallet: &str) -> Result<RelationshipResponse> {
+    let (wallet, target) = pair(wallet_address, target_wallet)?;
+
+    sqlx::query("DELETE FROM profile_blocks WHERE blocker_wallet = $1 AND blocked_wallet = $2")
+        .bind(&wallet)
+        .bind(&target)
+        .execute(pool)
+        .await?;
+
+    get_relationship(pool, &wallet, &target).await
+}
+
+/// Inbox ids the viewer has blocked or been blocked by, for filtering
+/// inbox-keyed data such as typing indicators
+pub async fn hidden_inbox_ids(pool: &DbPool, viewer_inbox_id: &str) -> Result<HashSet<String>> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT other.inbox_id
+        FROM user_profiles viewer
+        JOIN profile_blocks b ON b.blocker_wallet = viewer.wallet_address OR b.blocked_wallet = viewer.wallet_address
+        JOIN user_profiles other ON other.wallet_address =
+            CASE WHEN b.blocker_wallet = viewer.wallet_address THEN b.blocked_wallet ELSE b.blocker_wallet END
+        WHERE viewer.inbox_id = $1
+        "#,
+    )
+    .bind(viewer_inbox_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|(inbox_id,)| inbox_id).collect())
+}
+
+/// The viewer's contacts and block relations (either direction) among `wallets`
+#[derive(Default)]
+pub struct Relations {
+    pub contacts: HashSet<String>,
+    pub blocked: HashSet<String>,
+}
+
+pub async fn relations_with(pool: &DbPool, viewer_wallet: &str, wallets: &[String]) -> Result<Relations> {
+    let rows: Vec<(String, bool, bool)> = sqlx::query_as(
+        r#"
+        SELECT w,
+               EXISTS (SELECT 1 FROM profile_follows WHERE follower_wallet = $1 AND followee_wallet = w)
+                   AND EXISTS (SELECT 1 FROM profile_follows WHERE follower_wallet = w AND followee_wallet = $1),
+               EXISTS (
+                   SELECT 1 FROM profile_blocks
+                   WHERE (blocker_wallet = $1 AND blocked_wallet = w) OR (blocker_wallet = w AND blocked_wallet = $1)
+               )
+        FROM UNNEST($2::TEXT[]) AS w
+        "#,
+    )
+    .bind(viewer_wallet.to_lowercase())
+    .bind(wallets)
+    .fetch_all(pool)
+    .await?;
+
+    let mut relations = Relations::default();
+    for (wallet, contact, blocked) in rows {
+        if contact {
+            relations.contacts.insert(wallet.clone());
+        }
+        if blocked {
+            relations.blocked.insert(wallet);
+        }
+    }
+    Ok(relations)
+}
+
+// ── Lists ──
+
+pub async fn get_followers(
+    pool: &DbPool,
+    wallet: &str,
+    query: SocialListQuery,
+    viewer: Option<&str>,
+) -> Result<SocialListResponse> {
+    list(pool, Edge::Followers, wallet, query, viewer).await
+}
+
+pub async fn get_following(
+    pool: &DbPool,
+    wallet: &str,
+    query: SocialListQuery,
+    viewer: Option<&str>,
+) -> Result<SocialListResponse> {
+    list(pool, Edge::Following, wallet, query, viewer).await
+}
+
+/// Mutual follows, most recently connected first
+pub async fn get_contacts(
+    pool: &DbPool,
+    wallet: &str,
+    query: SocialListQuery,
+    viewer: Option<&str>,
+) -> Result<SocialListResponse> {
+    list(pool, Edge::Contacts, wallet, query, viewer).await
+}
+
+pub async fn get_blocked(
+    pool: &DbPool,
+    wallet: &str,
+    query: SocialListQuery,
+    viewer: Option<&str>,
+) -> Result<SocialListResponse> {
+    list(pool, Edge::Blocked, wallet, query, viewer).await
+}
+
+/// One page of a follow / block list. Entries the viewer may not see under the
+/// listed profiles' privacy settings are dropped from the page, not from `total`.
+async fn list(
+    pool: &DbPool,
+    edge: Edge,
+    wallet: &str,
+    query: SocialListQuery,
+    viewer: Option<&str>,
+) -> Result<SocialListResponse> {
+    let (from_and_where, since) = match edge {
+        Edge::Followers => (
+            "FROM profile_follows e JOIN user_profiles p ON p.wallet_address = e.follower_wallet WHERE e.followee_wallet = $1",
+            "e.created_at",
+        ),
+        Edge::Following => (
+            "FROM profile_follows e JOIN user_profiles p ON p.wallet_address = e.followee_wallet WHERE e.follower_wallet = $1",
+            "e.created_at",
+        ),
+        Edge::Contacts => (
+            r#"FROM profile_follows e
+               JOIN profile_follows back ON back.follower_wallet = e.followee_wallet AND back.followee_wallet = e.follower_wallet
+               JOIN user_profiles p ON p.wallet_address = e.followee_wallet
+               WHERE e.follower_wallet = $1"#,
+            "GREATEST(e.created_at, back.created_at)",
+        ),
+        Edge::Blocked => (
+            "FROM profile_blocks e JOIN user_profiles p ON p.wallet_address = e.blocked_wallet WHERE e.blocker_wallet = $1",
+            "e.created_at",
+        ),
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    // Ties broken by wallet so pages don't overlap
+    let sql = format!(
+        r#"
+        SELECT p.*, {since} AS since, COUNT(*) OVER () AS total
+        {from_and_where}
+        ORDER BY since DESC, p.wallet_address
+        LIMIT $2 OFFSET $3
+        "#
+    );
+
+    let rows = sqlx::query_as::<_, SocialRow>(&sql)
+        .bind(wallet.to_lowercase())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    let total = rows.first().map_or(0, |r| r.total);
+    let since: HashMap<String, DateTime<Utc>> =
+        rows.iter().map(|r| (r.profile.wallet_address.clone(), r.since)).collect();
+    let visible = profile_service::apply_privacy(pool, viewer, rows.into_iter().map(|r| r.profile).collect()).await?;
+
+    Ok(SocialListResponse {
+        profiles: visible
+            .into_iter()
+            .map(|profile| SocialListEntry {
+                since: since[&profile.wallet_address],
+                profile: SearchResult::from(profile),
+            })
+            .collect(),
+        total,
+        limit,
+        offset,
+    })
+}
+
+/// People followed by those the wallet follows, plus followers it hasn't followed
+/// back. Anyone already followed or blocked either way is left out.
+pub async fn get_suggestions(
+    pool: &DbPool,
+    wallet: &str,
+    limit: Option<i64>,
+    viewer: Option<&str>,
+) -> Result<Vec<FollowSuggestion>> {
+    let rows = sqlx::query_as::<_, SuggestionRow>(
+        r#"
+        WITH following AS (
+            SELECT followee_wallet AS wallet FROM profile_follows WHERE follower_wallet = $1
+        ), candidates AS (
+            SELECT f.followee_wallet AS wallet, f.follower_wallet AS via
+            FROM profile_follows f
+            JOIN following ON following.wallet = f.follower_wallet
+            UNION ALL
+            SELECT follower_wallet, NULL FROM profile_follows WHERE followee_wallet = $1
+        )
+        SELECT p.*, COUNT(DISTINCT c.via) AS mutual_count, BOOL_OR(c.via IS NULL) AS follows_you
+        FROM candidates c
+        JOIN user_profiles p ON p.wallet_address = c.wallet
+        WHERE c.wallet != $1
+          AND c.wallet NOT IN (SELECT wallet FROM following)
+          AND NOT EXISTS (
+              SELECT 1 FROM profile_blocks b
+              WHERE (b.blocker_wallet = $1 AND b.blocked_wallet = c.wallet)
+                 OR (b.blocker_wallet = c.wallet AND b.blocked_wallet = $1)
+          )
+        GROUP BY p.id
+        ORDER BY mutual_count DESC, follows_you DESC, p.created_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(wallet.to_lowercase())
+    .bind(limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_SUGGESTIONS))
+    .fetch_all(pool)
+    .await?;
+
+    let counts: HashMap<String, (i64, bool)> = rows
+        .iter()
+        .map(|r| (r.profile.wallet_address.clone(), (r.mutual_count, r.follows_you)))
+        .collect();
+    let visible = profile_service::apply_privacy(pool, viewer, rows.into_iter().map(|r| r.profile).collect()).await?;
+
+    Ok(visible
+        .into_iter()
+        .map(|profile| {
+            let (mutual_count, follows_you) = counts[&profile.wallet_address];
+            FollowSuggestion { profile: SearchResult::from(profile), mutual_count, follows_you }
+        })
+        .collect())
+}
+
+pub async fn get_relationship(pool: &DbPool, wallet_address: &str, other_wallet: &str) -> Result<RelationshipResponse> {
+    let relationship = sqlx::query_as::<_, RelationshipResponse>(
+        r#"
+        SELECT $1 AS wallet_address, $2 AS other_wallet,
+               EXISTS (SELECT 1 FROM profile_follows WHERE follower_wallet = $1 AND followee_wallet = $2) AS following,
+               EXISTS (SELECT 1 FROM profile_follows WHERE follower_wallet = $2 AND followee_wallet = $1) AS followed_by,
+               EXISTS (SELECT 1 FROM profile_follows WHERE follower_wallet = $1 AND followee_wallet = $2)
+                   AND EXISTS (SELECT 1 FROM profile_follows WHERE follower_wallet = $2 AND followee_wallet = $1) AS contacts,
+               EXISTS (SELECT 1 FROM profile_blocks WHERE blocker_wallet = $1 AND blocked_wallet = $2) AS blocking,
+               EXISTS (SELECT 1 FROM profile_blocks WHERE blocker_wallet = $2 AND blocked_wallet = $1) AS blocked_by
+        "#,
+    )
+    .bind(wallet_address.to_lowercase())
+    .bind(other_wallet.to_lowercase())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(relationship)
+}
+
+fn pair(wallet_address: &str, target_wallet: &str) -> Result<(String, String)> {
+    let wallet = wallet_address.to_lowercase();
+    let target = target_wallet.to_lowercase();
+    if wallet == target {
+        return Err(anyhow!("You can't follow or block yourself"));
+    }
+    Ok((wallet, target))
+}
+
+/// Both wallets need a profile; a missing one surfaces as RowNotFound
+async fn ensure_profiles(pool: &DbPool, wallet: &str, target: &str) -> Result<()> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM user_profiles WHERE wallet_address IN ($1, $2)")
+        .bind(wallet)
+        .bind(target)
+        .fetch_one(pool)
+        .await?;
+    if count < 2 {
+        return Err(sqlx::Error::RowNotFound.into());
+    }
+    Ok(())
+}
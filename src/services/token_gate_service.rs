@@ -1,11 +1,31 @@
 use crate::db::DbPool;
 use crate::models::{
-    CreateTokenGateRequest, TokenGate, TokenGateResponse, TokenRequirementResponse,
+    CreateTokenGateRequest, TokenGate, TokenGateResponse, TokenRequirement, TokenRequirementResponse,
     VerifyTokenGateRequest, VerifyTokenGateResponse, RequirementStatus,
+    GateMembershipStatus, GateReverifySettings, GateEvictionEntry, GateEvictionReport,
+    ReverifySettingsResponse, UpdateReverifySettingsRequest,
+    SimulateTokenGateRequest, SimulateTokenGateResponse, SimulatedWalletResult, SimulationSummary,
 };
+use crate::models::UserProfile;
+use crate::services::{basename_service, eas_service, log_scanner};
+use anyhow::anyhow;
+use chrono::Utc;
+use futures_util::stream::{self, StreamExt};
 use std::env;
 use ethers::prelude::*;
 
+/// Grace period applied when a conversation has no gate_reverify_settings row
+pub const DEFAULT_GRACE_PERIOD_HOURS: i32 = 24;
+
+/// Simulations are capped to keep RPC usage bounded
+pub const MAX_SIMULATION_WALLETS: usize = 100;
+const SIMULATION_CONCURRENCY: usize = 5;
+
+// Holding-duration checks replay Transfer logs over this many blocks per day.
+// Base produces a block every 2 seconds.
+const BLOCK_TIME_SECS: u64 = 2;
+const BLOCKS_PER_DAY: u64 = 86_400 / BLOCK_TIME_SECS;
+
 // ERC-20 balanceOf ABI
 abigen!(
     ERC20,
@@ -29,11 +49,13 @@ pub async fn create_or_update_token_gates(
         .await?;
 
     // Insert new gates
-    for requirement in req.requirements {
+    for requirement in req.requirements.into_iter().map(normalize_requirement) {
         sqlx::query(
             r#"
-            INSERT INTO token_gates (conversation_id, token_address, token_symbol, min_amount, operator)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO token_gates (conversation_id, token_address, token_symbol, min_amount, operator,
+                                     min_hold_days, requirement_type, min_profile_age_days,
+                                     eas_schema_uid, eas_attester)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#
         )
         .bind(conversation_id)
@@ -41,6 +63,11 @@ pub async fn create_or_update_token_gates(
         .bind(&requirement.token_symbol)
         .bind(&requirement.min_amount)
         .bind(&req.operator)
+        .bind(requirement.min_hold_days)
+        .bind(&requirement.requirement_type)
+        .bind(requirement.min_profile_age_days)
+        .bind(&requirement.eas_schema_uid)
+        .bind(&requirement.eas_attester)
         .execute(&mut *tx)
         .await?;
     }
@@ -49,6 +76,62 @@ pub async fn create_or_update_token_gates(
     Ok(())
 }
 
+/// Fill in labels for non-token requirements and drop fields that don't apply to them
+pub fn normalize_requirement(mut requirement: TokenRequirement) -> TokenRequirement {
+    let label = match requirement.requirement_type.as_str() {
+        "basename" => "Basename",
+        "profile_age" => "Profile age",
+        "username" => "Username",
+        "eas" => "Attestation",
+        _ => return requirement,
+    };
+
+    if requirement.token_symbol.is_empty() {
+        requirement.token_symbol = label.to_string();
+    }
+    requirement.token_address = None;
+    requirement.min_amount = "0".to_string();
+    requirement.min_hold_days = None;
+    requirement
+}
+
+/// Check a requirement against the rules token_gates enforces with constraints, for
+/// requirements stored outside that table (shop item rules)
+pub fn validate_requirement(requirement: &TokenRequirement) -> Result<(), String> {
+    match requirement.requirement_type.as_str() {
+        "token" => {
+            if requirement.token_symbol.trim().is_empty() {
+                return Err("Token requirements need a token_symbol".to_string());
+            }
+            if U256::from_dec_str(&requirement.min_amount).is_err() {
+                return Err("Token requirements need min_amount in base units".to_string());
+            }
+            if requirement.min_hold_days.is_some_and(|d| !(1..=365).contains(&d)) {
+                return Err("min_hold_days must be 1-365".to_string());
+            }
+            if requirement.min_hold_days.is_some() && requirement.token_address.is_none() {
+                return Err("min_hold_days needs an ERC-20 token_address".to_string());
+            }
+        }
+        "profile_age" => {
+            if !requirement.min_profile_age_days.is_some_and(|d| (1..=3650).contains(&d)) {
+                return Err("Profile age requirements need min_profile_age_days 1-3650".to_string());
+            }
+        }
+        "eas" => {
+            let valid_uid = requirement.eas_schema_uid.as_deref().is_some_and(|uid| {
+                uid.len() == 66 && uid.starts_with("0x") && uid[2..].chars().all(|c| c.is_ascii_hexdigit())
+            });
+            if !valid_uid {
+                return Err("EAS requirements need a 32-byte hex eas_schema_uid".to_string());
+            }
+        }
+        "basename" | "username" => {}
+        other => return Err(format!("Unknown requirement_type '{}'", other)),
+    }
+    Ok(())
+}
+
 pub async fn get_token_gates(
     pool: &DbPool,
     conversation_id: &str,
@@ -84,6 +167,40 @@ pub async fn delete_token_gates(
     Ok(())
 }
 
+/// Deterministic hash of a gate configuration. Embedded in gate passes so a pass
+/// stops matching once the owner changes the requirements.
+pub fn compute_gate_hash(gates: &[TokenGate]) -> String {
+    let operator = gates.first().map(|g| g.operator.as_str()).unwrap_or("");
+    let mut requirements: Vec<String> = gates
+        .iter()
+        .cloned()
+        .map(TokenRequirementResponse::from)
+        .map(|r| serde_json::to_string(&r).unwrap_or_default())
+        .collect();
+    requirements.sort();
+
+    let canonical = format!("{}|{}", operator, requirements.join("|"));
+    format!("0x{}", hex::encode(ethers::utils::keccak256(canonical.as_bytes())))
+}
+
+/// Current gate hash for a conversation, None when it has no gates
+pub async fn get_gate_hash(
+    pool: &DbPool,
+    conversation_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let gates: Vec<TokenGate> = sqlx::query_as(
+        "SELECT * FROM token_gates WHERE conversation_id = $1"
+    )
+    .bind(conversation_id)
+    .fetch_all(pool)
+    .await?;
+
+    if gates.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(compute_gate_hash(&gates)))
+}
+
 pub async fn verify_token_gates(
     pool: &DbPool,
     req: VerifyTokenGateRequest,
@@ -101,44 +218,287 @@ pub async fn verify_token_gates(
         return Ok(VerifyTokenGateResponse {
             allowed: true,
             requirements_met: vec![],
+            block_number: None,
+            gate_hash: None,
+            gate_pass: None,
+            gate_pass_expires_at: None,
         });
     }
 
-    let operator = gates.first().unwrap().operator.clone();
-    let user_address: Address = req.wallet_address.parse()?;
+    let gate_hash = compute_gate_hash(&gates);
+    let provider = base_provider()?;
+
+    // Pin every balance read to the same block so the result can be attested
+    let block_number = provider.get_block_number().await?;
+
+    let (allowed, requirements_met) =
+        evaluate_gates(pool, &provider, &gates, &req.wallet_address, block_number).await?;
+
+    Ok(VerifyTokenGateResponse {
+        allowed,
+        requirements_met,
+        block_number: Some(block_number.as_u64()),
+        gate_hash: Some(gate_hash),
+        gate_pass: None,
+        gate_pass_expires_at: None,
+    })
+}
+
+/// Dry-run a gate (draft or saved) against a list of wallets. Nothing is persisted.
+pub async fn simulate_token_gates(
+    pool: &DbPool,
+    conversation_id: &str,
+    req: SimulateTokenGateRequest,
+) -> Result<Option<SimulateTokenGateResponse>, Box<dyn std::error::Error>> {
+    if req.wallet_addresses.len() > MAX_SIMULATION_WALLETS {
+        return Err(anyhow!("At most {} wallets can be simulated at once", MAX_SIMULATION_WALLETS).into());
+    }
+
+    let (source, gates) = match req.requirements {
+        Some(requirements) => {
+            if requirements.is_empty() {
+                return Err(anyhow!("Draft gate needs at least one requirement").into());
+            }
+            let operator = req.operator.unwrap_or_else(|| "AND".to_string());
+            ("draft", draft_gates(conversation_id, requirements, &operator))
+        }
+        None => {
+            let gates: Vec<TokenGate> = sqlx::query_as(
+                "SELECT * FROM token_gates WHERE conversation_id = $1 ORDER BY created_at ASC"
+            )
+            .bind(conversation_id)
+            .fetch_all(pool)
+            .await?;
+            if gates.is_empty() {
+                return Ok(None);
+            }
+            ("saved", gates)
+        }
+    };
+
+    let gate_hash = compute_gate_hash(&gates);
+    let provider = base_provider()?;
+    let block_number = provider.get_block_number().await?;
+
+    // Errors are stringified per wallet so one bad address doesn't sink the run
+    let results: Vec<SimulatedWalletResult> = stream::iter(req.wallet_addresses)
+        .map(|wallet_address| {
+            let provider = &provider;
+            let gates = &gates;
+            async move {
+                let evaluated = evaluate_gates(pool, provider, gates, &wallet_address, block_number)
+                    .await
+                    .map_err(|e| e.to_string());
+                match evaluated {
+                    Ok((allowed, requirements_met)) => SimulatedWalletResult {
+                        wallet_address,
+                        allowed,
+                        requirements_met,
+                        error: None,
+                    },
+                    Err(e) => SimulatedWalletResult {
+                        wallet_address,
+                        allowed: false,
+                        requirements_met: vec![],
+                        error: Some(e),
+                    },
+                }
+            }
+        })
+        .buffered(SIMULATION_CONCURRENCY)
+        .collect()
+        .await;
+
+    let total = results.len();
+    let errored = results.iter().filter(|r| r.error.is_some()).count();
+    let passed = results.iter().filter(|r| r.allowed).count();
+    let evaluated = total - errored;
+    let summary = SimulationSummary {
+        total,
+        passed,
+        failed: evaluated - passed,
+        errored,
+        pass_rate: if evaluated > 0 { passed as f64 / evaluated as f64 } else { 0.0 },
+    };
+
+    let operator = gates.first().map(|g| g.operator.clone()).unwrap_or_default();
+    let gate = TokenGateResponse {
+        requirements: gates.into_iter().map(TokenRequirementResponse::from).collect(),
+        operator,
+    };
+
+    Ok(Some(SimulateTokenGateResponse {
+        source: source.to_string(),
+        gate,
+        gate_hash,
+        block_number: block_number.as_u64(),
+        results,
+        summary,
+    }))
+}
+
+/// In-memory gate rows for a draft config, shaped like what create_or_update_token_gates stores
+fn draft_gates(
+    conversation_id: &str,
+    requirements: Vec<TokenRequirement>,
+    operator: &str,
+) -> Vec<TokenGate> {
+    let now = Utc::now();
+    requirements
+        .into_iter()
+        .map(normalize_requirement)
+        .map(|r| TokenGate {
+            id: uuid::Uuid::nil(),
+            conversation_id: conversation_id.to_string(),
+            token_address: r.token_address,
+            token_symbol: r.token_symbol,
+            min_amount: r.min_amount,
+            operator: operator.to_string(),
+            min_hold_days: r.min_hold_days,
+            requirement_type: r.requirement_type,
+            min_profile_age_days: r.min_profile_age_days,
+            eas_schema_uid: r.eas_schema_uid,
+            eas_attester: r.eas_attester,
+            created_at: now,
+            updated_at: now,
+        })
+        .collect()
+}
 
+pub fn base_provider() -> Result<Provider<Http>, Box<dyn std::error::Error>> {
     // Get Base RPC URL from env
     let rpc_url = env::var("BASE_RPC_URL")
         .unwrap_or_else(|_| "https://mainnet.base.org".to_string());
-    let provider = Provider::<Http>::try_from(rpc_url)?;
+    Ok(Provider::<Http>::try_from(rpc_url)?)
+}
+
+/// Evaluate requirements that aren't stored as a conversation gate (shop item rules)
+/// for one wallet, exactly as a gate with the same requirements would be
+pub async fn evaluate_requirements(
+    pool: &DbPool,
+    provider: &Provider<Http>,
+    requirements: Vec<TokenRequirement>,
+    operator: &str,
+    wallet_address: &str,
+    block_number: U64,
+) -> Result<(bool, Vec<RequirementStatus>), Box<dyn std::error::Error>> {
+    let gates = draft_gates("", requirements, operator);
+    evaluate_gates(pool, provider, &gates, wallet_address, block_number).await
+}
+
+/// Evaluate a set of gate requirements for one wallet at a fixed block.
+/// Shared by verification, member re-verification and the simulator.
+async fn evaluate_gates(
+    pool: &DbPool,
+    provider: &Provider<Http>,
+    gates: &[TokenGate],
+    wallet_address: &str,
+    block_number: U64,
+) -> Result<(bool, Vec<RequirementStatus>), Box<dyn std::error::Error>> {
+    let operator = gates.first().map(|g| g.operator.clone()).unwrap_or_default();
+    let user_address: Address = wallet_address.parse()?;
+    let block = BlockId::from(block_number);
+
+    // Loaded on the first profile-based requirement, shared by the rest
+    let mut profile: Option<Option<UserProfile>> = None;
 
     let mut requirements_met = Vec::new();
     let mut all_met = true;
     let mut any_met = false;
 
     for gate in gates {
+        if gate.requirement_type == "eas" {
+            let status = check_eas_requirement(provider, gate, user_address, block_number).await?;
+            if status.met {
+                any_met = true;
+            } else {
+                all_met = false;
+            }
+            requirements_met.push(status);
+            continue;
+        }
+
+        if gate.requirement_type != "token" {
+            if profile.is_none() {
+                profile = Some(
+                    sqlx::query_as("SELECT * FROM user_profiles WHERE LOWER(wallet_address) = LOWER($1)")
+                        .bind(wallet_address)
+                        .fetch_optional(pool)
+                        .await?,
+                );
+            }
+            let status = check_profile_requirement(
+                provider,
+                gate,
+                profile.as_ref().and_then(|p| p.as_ref()),
+                user_address,
+                block,
+            )
+            .await?;
+
+            if status.met {
+                any_met = true;
+            } else {
+                all_met = false;
+            }
+            requirements_met.push(status);
+            continue;
+        }
+
         let balance_met = check_balance(
-            &provider,
+            provider,
             gate.token_address.as_deref(),
             user_address,
             &gate.min_amount,
+            block,
         )
         .await?;
 
-        let status = RequirementStatus {
+        let mut status = RequirementStatus {
+            requirement_type: gate.requirement_type.clone(),
             token: gate.token_symbol.clone(),
             required: gate.min_amount.clone(),
             balance: balance_met.1.clone(),
             met: balance_met.0,
+            min_hold_days: gate.min_hold_days,
+            held_days: None,
+            held_since: None,
         };
 
-        requirements_met.push(status);
+        // Only worth walking back through history if the current balance qualifies
+        if let Some(min_hold_days) = gate.min_hold_days.filter(|d| *d > 0) {
+            match gate.token_address.as_deref() {
+                Some(token_address) if status.met => {
+                    let (held_days, since_block) = check_holding_period(
+                        provider,
+                        token_address.parse()?,
+                        user_address,
+                        U256::from_dec_str(&status.balance)?,
+                        U256::from_dec_str(&gate.min_amount)?,
+                        block_number,
+                        min_hold_days as u64,
+                    )
+                    .await?;
 
-        if balance_met.0 {
+                    status.met = held_days >= min_hold_days as u64;
+                    status.held_days = Some(held_days as i64);
+                    status.held_since = block_timestamp(provider, since_block).await?;
+                }
+                // Native ETH has no Transfer logs to replay, so its holding period can't be shown
+                _ => {
+                    status.met = false;
+                    status.held_days = Some(0);
+                }
+            }
+        }
+
+        if status.met {
             any_met = true;
         } else {
             all_met = false;
         }
+
+        requirements_met.push(status);
     }
 
     let allowed = match operator.as_str() {
@@ -147,9 +507,106 @@ pub async fn verify_token_gates(
         _ => false,
     };
 
-    Ok(VerifyTokenGateResponse {
-        allowed,
-        requirements_met,
+    Ok((allowed, requirements_met))
+}
+
+/// Basename, profile age and username requirements, checked against the wallet's
+/// BlocChat profile. A wallet without a profile fails all of them.
+async fn check_profile_requirement(
+    provider: &Provider<Http>,
+    gate: &TokenGate,
+    profile: Option<&UserProfile>,
+    user_address: Address,
+    block: BlockId,
+) -> Result<RequirementStatus, Box<dyn std::error::Error>> {
+    let (required, current, met) = match gate.requirement_type.as_str() {
+        "basename" => {
+            // The stored basename only counts if it still resolves to this wallet on-chain
+            let basename = profile.and_then(|p| p.basename.as_deref());
+            let verified = match basename {
+                Some(name) => {
+                    basename_service::basename_owned_by(provider, name, user_address, Some(block)).await?
+                }
+                None => false,
+            };
+            (
+                "verified Basename".to_string(),
+                basename.unwrap_or("none").to_string(),
+                verified,
+            )
+        }
+        "profile_age" => {
+            let min_days = gate.min_profile_age_days.unwrap_or(0) as i64;
+            let age_days = profile
+                .map(|p| (Utc::now() - p.created_at).num_days())
+                .unwrap_or(0);
+            (
+                format!("{} days", min_days),
+                format!("{} days", age_days),
+                profile.is_some() && age_days >= min_days,
+            )
+        }
+        "username" => {
+            let username = profile.and_then(|p| p.username.as_deref());
+            (
+                "claimed username".to_string(),
+                username.unwrap_or("none").to_string(),
+                username.is_some(),
+            )
+        }
+        other => return Err(anyhow!("Unknown requirement type: {}", other).into()),
+    };
+
+    Ok(RequirementStatus {
+        requirement_type: gate.requirement_type.clone(),
+        token: gate.token_symbol.clone(),
+        required,
+        balance: current,
+        met,
+        min_hold_days: None,
+        held_days: None,
+        held_since: None,
+    })
+}
+
+/// EAS requirement: the wallet holds a valid attestation for the schema
+/// (from the given attester, if set) at the checked block
+async fn check_eas_requirement(
+    provider: &Provider<Http>,
+    gate: &TokenGate,
+    user_address: Address,
+    block_number: U64,
+) -> Result<RequirementStatus, Box<dyn std::error::Error>> {
+    let schema_uid = gate
+        .eas_schema_uid
+        .as_deref()
+        .ok_or_else(|| anyhow!("EAS requirement without a schema UID"))?;
+
+    let attestation = eas_service::find_valid_attestation(
+        provider,
+        schema_uid,
+        gate.eas_attester.as_deref(),
+        user_address,
+        block_number,
+    )
+    .await?;
+
+    let required = match &gate.eas_attester {
+        Some(attester) => format!("attestation {} by {}", schema_uid, attester),
+        None => format!("attestation {}", schema_uid),
+    };
+
+    Ok(RequirementStatus {
+        requirement_type: gate.requirement_type.clone(),
+        token: gate.token_symbol.clone(),
+        required,
+        balance: attestation
+            .map(|uid| format!("{:?}", uid))
+            .unwrap_or_else(|| "none".to_string()),
+        met: attestation.is_some(),
+        min_hold_days: None,
+        held_days: None,
+        held_since: None,
     })
 }
 
@@ -158,24 +615,406 @@ async fn check_balance(
     token_address: Option<&str>,
     user_address: Address,
     min_amount: &str,
+    block: BlockId,
 ) -> Result<(bool, String), Box<dyn std::error::Error>> {
-    let balance: U256 = if let Some(token_addr) = token_address {
-        // ERC-20 token
-        let token_address: Address = token_addr.parse()?;
-        let contract = ERC20::new(token_address, provider.clone().into());
-        contract.balance_of(user_address).call().await?
-    } else {
-        // Native ETH
-        provider.get_balance(user_address, None).await?
-    };
+    let balance = balance_at(provider, token_address, user_address, block).await?;
 
     // Convert balance to string for comparison
     let balance_str = balance.to_string();
     
-    // Parse min_amount as U256
-    let min_amount_u256: U256 = min_amount.parse()?;
+    // min_amount is a decimal string; U256's FromStr would read it as hex
+    let min_amount_u256 = U256::from_dec_str(min_amount)?;
     
     let met = balance >= min_amount_u256;
 
     Ok((met, balance_str))
 }
+
+async fn balance_at(
+    provider: &Provider<Http>,
+    token_address: Option<&str>,
+    user_address: Address,
+    block: BlockId,
+) -> Result<U256, Box<dyn std::error::Error>> {
+    let balance = if let Some(token_addr) = token_address {
+        // ERC-20 token
+        let token_address: Address = token_addr.parse()?;
+        let contract = ERC20::new(token_address, provider.clone().into());
+        contract.balance_of(user_address).block(block).call().await?
+    } else {
+        // Native ETH
+        provider.get_balance(user_address, Some(block)).await?
+    };
+    Ok(balance)
+}
+
+/// How long `user_address` has held at least `min_amount` of `token`, replayed from
+/// its Transfer logs: starting from `balance` at `block_number`, transfers are undone
+/// newest first until the balance before one drops under `min_amount`. Any dip counts,
+/// however short. Returns full days held (capped at `min_hold_days`) and the block the
+/// holding started at, or the start of the window when it was held throughout.
+async fn check_holding_period(
+    provider: &Provider<Http>,
+    token: Address,
+    user_address: Address,
+    balance: U256,
+    min_amount: U256,
+    block_number: U64,
+    min_hold_days: u64,
+) -> Result<(u64, U64), Box<dyn std::error::Error>> {
+    let head = block_number.as_u64();
+    let window_start = head.saturating_sub(min_hold_days * BLOCKS_PER_DAY);
+
+    let transfers = Filter::new().address(token).event("Transfer(address,address,uint256)");
+    let filters = [transfers.clone().topic1(user_address), transfers.topic2(user_address)];
+
+    let mut balance = balance;
+    let mut started_at = None;
+    log_scanner::scan_backwards(provider, &filters, window_start + 1, head, |logs| {
+        started_at = undo_transfers(user_address, &mut balance, min_amount, logs);
+        started_at.is_none()
+    })
+    .await?;
+
+    let since_block = started_at.map_or(window_start, |b| b.as_u64());
+    let held_days = (head - since_block) / BLOCKS_PER_DAY;
+    Ok((held_days.min(min_hold_days), U64::from(since_block)))
+}
+
+/// Undo `logs` (newest first) on `balance`, the wallet's balance after the newest
+/// one. Returns the block of the transfer the balance last rose to `min_amount` at,
+/// if it happened within these logs.
+fn undo_transfers(wallet: Address, balance: &mut U256, min_amount: U256, logs: &[Log]) -> Option<U64> {
+    for log in logs {
+        // ERC-721 Transfer shares the signature but indexes the token id
+        if log.topics.len() != 3 || log.data.len() != 32 {
+            continue;
+        }
+        let from = Address::from(log.topics[1]);
+        let to = Address::from(log.topics[2]);
+        let amount = U256::from_big_endian(&log.data);
+
+        if to == wallet && from != wallet {
+            *balance = balance.saturating_sub(amount);
+        } else if from == wallet && to != wallet {
+            *balance = balance.saturating_add(amount);
+        }
+        if *balance < min_amount {
+            return Some(log.block_number.unwrap_or_default());
+        }
+    }
+    None
+}
+
+async fn block_timestamp(
+    provider: &Provider<Http>,
+    block_number: U64,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let block = provider.get_block(block_number).await?;
+    Ok(block
+        .and_then(|b| chrono::DateTime::from_timestamp(b.timestamp.as_u64() as i64, 0))
+        .map(|t| t.to_rfc3339()))
+}
+
+// ── Membership re-verification ──
+
+/// Record the outcome of a gate check for a known member.
+/// `failing_since` is kept from the first failed check until the member passes again.
+pub async fn record_membership_check(
+    pool: &DbPool,
+    conversation_id: &str,
+    wallet_address: &str,
+    allowed: bool,
+    requirements_met: &[RequirementStatus],
+) -> Result<(), sqlx::Error> {
+    let requirements_json = serde_json::to_value(requirements_met).unwrap_or_default();
+
+    sqlx::query(
+        r#"INSERT INTO gate_membership_status
+             (conversation_id, wallet_address, qualifies, requirements_met, last_checked_at, failing_since)
+           VALUES ($1, $2, $3, $4, NOW(), CASE WHEN $3 THEN NULL ELSE NOW() END)
+           ON CONFLICT (conversation_id, wallet_address) DO UPDATE SET
+             qualifies = EXCLUDED.qualifies,
+             requirements_met = EXCLUDED.requirements_met,
+             last_checked_at = NOW(),
+             failing_since = CASE
+               WHEN EXCLUDED.qualifies THEN NULL
+               ELSE COALESCE(gate_membership_status.failing_since, NOW())
+             END,
+             notified_at = CASE
+               WHEN EXCLUDED.qualifies THEN NULL
+               ELSE gate_membership_status.notified_at
+             END,
+             last_error = NULL"#,
+    )
+    .bind(conversation_id)
+    .bind(wallet_address.to_lowercase())
+    .bind(allowed)
+    .bind(&requirements_json)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Record a failed check attempt (RPC error etc.) without changing the member's status
+pub async fn record_membership_error(
+    pool: &DbPool,
+    conversation_id: &str,
+    wallet_address: &str,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"UPDATE gate_membership_status
+           SET last_checked_at = NOW(), last_error = $3
+           WHERE conversation_id = $1 AND wallet_address = $2"#,
+    )
+    .bind(conversation_id)
+    .bind(wallet_address.to_lowercase())
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Replace the known member list of a conversation. New members are checked on the next pass.
+pub async fn sync_members(
+    pool: &DbPool,
+    conversation_id: &str,
+    wallet_addresses: &[String],
+) -> Result<usize, sqlx::Error> {
+    let wallets: Vec<String> = wallet_addresses.iter().map(|w| w.to_lowercase()).collect();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "DELETE FROM gate_membership_status WHERE conversation_id = $1 AND NOT (wallet_address = ANY($2))",
+    )
+    .bind(conversation_id)
+    .bind(&wallets)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"INSERT INTO gate_membership_status (conversation_id, wallet_address)
+           SELECT $1, UNNEST($2::TEXT[])
+           ON CONFLICT (conversation_id, wallet_address) DO NOTHING"#,
+    )
+    .bind(conversation_id)
+    .bind(&wallets)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(wallets.len())
+}
+
+pub async fn remove_member(
+    pool: &DbPool,
+    conversation_id: &str,
+    wallet_address: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM gate_membership_status WHERE conversation_id = $1 AND wallet_address = $2",
+    )
+    .bind(conversation_id)
+    .bind(wallet_address.to_lowercase())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Members of still-gated conversations whose last check is older than `recheck_secs`
+pub async fn get_members_due_for_check(
+    pool: &DbPool,
+    recheck_secs: i64,
+    limit: i64,
+) -> Result<Vec<GateMembershipStatus>, sqlx::Error> {
+    sqlx::query_as::<_, GateMembershipStatus>(
+        r#"SELECT m.* FROM gate_membership_status m
+           WHERE EXISTS (SELECT 1 FROM token_gates g WHERE g.conversation_id = m.conversation_id)
+             AND (m.last_checked_at IS NULL
+                  OR m.last_checked_at < NOW() - ($1 || ' seconds')::INTERVAL)
+           ORDER BY m.last_checked_at ASC NULLS FIRST
+           LIMIT $2"#,
+    )
+    .bind(recheck_secs)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_reverify_settings(
+    pool: &DbPool,
+    conversation_id: &str,
+) -> Result<ReverifySettingsResponse, sqlx::Error> {
+    let settings = sqlx::query_as::<_, GateReverifySettings>(
+        "SELECT * FROM gate_reverify_settings WHERE conversation_id = $1",
+    )
+    .bind(conversation_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match settings {
+        Some(s) => ReverifySettingsResponse::from(s),
+        None => ReverifySettingsResponse {
+            conversation_id: conversation_id.to_string(),
+            grace_period_hours: DEFAULT_GRACE_PERIOD_HOURS,
+            webhook_url: None,
+        },
+    })
+}
+
+pub async fn update_reverify_settings(
+    pool: &DbPool,
+    conversation_id: &str,
+    req: UpdateReverifySettingsRequest,
+) -> Result<GateReverifySettings, sqlx::Error> {
+    let current = get_reverify_settings(pool, conversation_id).await?;
+    let grace_period_hours = req.grace_period_hours.unwrap_or(current.grace_period_hours).max(0);
+    // An empty string clears the webhook
+    let webhook_url = match req.webhook_url {
+        Some(url) if url.trim().is_empty() => None,
+        Some(url) => Some(url),
+        None => current.webhook_url,
+    };
+
+    sqlx::query_as::<_, GateReverifySettings>(
+        r#"INSERT INTO gate_reverify_settings (conversation_id, grace_period_hours, webhook_url)
+           VALUES ($1, $2, $3)
+           ON CONFLICT (conversation_id) DO UPDATE SET
+             grace_period_hours = EXCLUDED.grace_period_hours,
+             webhook_url = EXCLUDED.webhook_url
+           RETURNING *"#,
+    )
+    .bind(conversation_id)
+    .bind(grace_period_hours)
+    .bind(&webhook_url)
+    .fetch_one(pool)
+    .await
+}
+
+/// Members that have failed their gate for longer than the grace period.
+/// With `unnotified_only`, members already sent to the webhook are left out.
+pub async fn get_eviction_report(
+    pool: &DbPool,
+    conversation_id: &str,
+    unnotified_only: bool,
+) -> Result<GateEvictionReport, sqlx::Error> {
+    let settings = get_reverify_settings(pool, conversation_id).await?;
+
+    let members = sqlx::query_as::<_, GateMembershipStatus>(
+        r#"SELECT * FROM gate_membership_status
+           WHERE conversation_id = $1
+             AND qualifies = false
+             AND failing_since <= NOW() - make_interval(hours => $2)
+             AND (NOT $3 OR notified_at IS NULL)
+           ORDER BY failing_since ASC"#,
+    )
+    .bind(conversation_id)
+    .bind(settings.grace_period_hours)
+    .bind(unnotified_only)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(GateEvictionReport {
+        conversation_id: conversation_id.to_string(),
+        grace_period_hours: settings.grace_period_hours,
+        members: members.into_iter().map(GateEvictionEntry::from).collect(),
+        generated_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// (conversation_id, webhook_url) pairs with evictions past grace that have not been sent yet
+pub async fn get_pending_eviction_webhooks(
+    pool: &DbPool,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, String)>(
+        r#"SELECT DISTINCT s.conversation_id, s.webhook_url
+           FROM gate_reverify_settings s
+           JOIN gate_membership_status m ON m.conversation_id = s.conversation_id
+           WHERE s.webhook_url IS NOT NULL
+             AND m.qualifies = false
+             AND m.notified_at IS NULL
+             AND m.failing_since <= NOW() - make_interval(hours => s.grace_period_hours)"#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn mark_evictions_notified(
+    pool: &DbPool,
+    conversation_id: &str,
+    wallet_addresses: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"UPDATE gate_membership_status SET notified_at = NOW()
+           WHERE conversation_id = $1 AND wallet_address = ANY($2)"#,
+    )
+    .bind(conversation_id)
+    .bind(wallet_addresses)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn gate(token_symbol: &str, min_amount: &str) -> TokenGate {
+        TokenGate {
+            id: Uuid::new_v4(),
+            conversation_id: "conv-1".to_string(),
+            token_address: None,
+            token_symbol: token_symbol.to_string(),
+            min_amount: min_amount.to_string(),
+            operator: "AND".to_string(),
+            min_hold_days: None,
+            requirement_type: "token".to_string(),
+            min_profile_age_days: None,
+            eas_schema_uid: None,
+            eas_attester: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_gate_hash_ignores_row_order() {
+        let a = compute_gate_hash(&[gate("ETH", "1"), gate("USDC", "100")]);
+        let b = compute_gate_hash(&[gate("USDC", "100"), gate("ETH", "1")]);
+        assert_eq!(a, b);
+
+        let changed = compute_gate_hash(&[gate("ETH", "2"), gate("USDC", "100")]);
+        assert_ne!(a, changed);
+    }
+
+    fn transfer(block: u64, from: Address, to: Address, amount: u64) -> Log {
+        let mut data = [0u8; 32];
+        U256::from(amount).to_big_endian(&mut data);
+        Log {
+            topics: vec![H256::zero(), H256::from(from), H256::from(to)],
+            data: data.to_vec().into(),
+            block_number: Some(U64::from(block)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_undo_transfers_catches_same_day_sell_and_rebuy() {
+        let wallet = Address::repeat_byte(1);
+        let dex = Address::repeat_byte(2);
+        let min_amount = U256::from(1000);
+
+        // Newest first: sold at block 110 and bought back at block 120, the same day
+        let logs = [transfer(120, dex, wallet, 1000), transfer(110, wallet, dex, 1000), transfer(10, dex, wallet, 1000)];
+        let mut balance = U256::from(1000);
+        assert_eq!(undo_transfers(wallet, &mut balance, min_amount, &logs), Some(U64::from(120)));
+
+        // Incoming top-ups and self transfers never take the balance under the minimum
+        let logs = [transfer(120, dex, wallet, 500), transfer(110, wallet, wallet, 5000)];
+        let mut balance = U256::from(2000);
+        assert_eq!(undo_transfers(wallet, &mut balance, min_amount, &logs), None);
+        assert_eq!(balance, U256::from(1500));
+    }
+}